@@ -0,0 +1,341 @@
+// SPDX-FileCopyrightText: 2022 - 2024 Ali Sajid Imami
+//
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use rand::{
+    seq::IndexedRandom,
+    SeedableRng,
+};
+
+use crate::{
+    Board,
+    Coord,
+    Game,
+    GameError,
+    GameResult,
+    GameStatus,
+    Seeded,
+    SeededRng,
+    Symbol,
+};
+
+/// A controller that can play one side of a game of Tic Tac Toe.
+///
+/// Humans, random players, search engines and learning machines such as MENACE all implement
+/// this trait, so any two of them can be paired with [`play_match`].
+pub trait Agent {
+    /// Chooses the next move for the given position.
+    ///
+    /// # Arguments
+    ///
+    /// * `board` - The current position. The game is still in progress.
+    /// * `symbol` - The symbol the agent plays with.
+    ///
+    /// # Returns
+    ///
+    /// The square to play, or `None` if the agent resigns.
+    fn choose_move(&mut self, board: &Board, symbol: Symbol) -> Option<Coord>;
+
+    /// Called once the game is over, so that learning agents can update themselves.
+    ///
+    /// # Arguments
+    ///
+    /// * `outcome` - How the game ended.
+    /// * `symbol` - The symbol the agent played with.
+    fn on_game_end(&mut self, _outcome: &MatchOutcome, _symbol: Symbol) {}
+}
+
+/// How a game between two agents ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MatchOutcome {
+    /// The game was played to the end and was won or drawn.
+    Finished(GameStatus),
+    /// The player with the given symbol resigned before the game was over.
+    Resigned(Symbol),
+}
+
+impl MatchOutcome {
+    /// Determines the result of the match for one of the players.
+    ///
+    /// A resignation counts as a loss for the player who resigned and a win for their opponent.
+    #[must_use]
+    pub fn result_for(&self, symbol: Symbol) -> GameResult {
+        match self {
+            Self::Finished(status) => {
+                GameResult::for_player(status, symbol).unwrap_or(GameResult::Draw)
+            }
+            Self::Resigned(resigned) if *resigned == symbol => GameResult::Loss,
+            Self::Resigned(_) => GameResult::Win,
+        }
+    }
+
+    /// Returns the symbol of the winning player, if any.
+    #[must_use]
+    pub const fn winner(&self) -> Option<Symbol> {
+        match self {
+            Self::Finished(status) => status.winner(),
+            Self::Resigned(resigned) => Some(resigned.opponent()),
+        }
+    }
+}
+
+/// The record of a game played by [`play_match`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MatchRecord {
+    /// How the game ended.
+    pub outcome: MatchOutcome,
+    /// The moves played, in order, starting with `X`.
+    pub moves:   Vec<Coord>,
+    /// The final position.
+    pub board:   Board,
+}
+
+/// Plays a complete game between two agents.
+///
+/// The agents take turns choosing moves on a fresh [`Game`], starting with `player_x`, until the
+/// game is won, drawn, or one of them resigns. Both agents are then told the outcome through
+/// [`Agent::on_game_end`].
+///
+/// # Arguments
+///
+/// * `player_x` - The agent playing `X`, who moves first.
+/// * `player_o` - The agent playing `O`.
+///
+/// # Errors
+///
+/// Returns the [`GameError`] raised by [`Game::play`] if an agent chooses an illegal move. The
+/// offending agent is treated as having resigned, and both agents are told so before the error is
+/// returned, so that neither is left holding the moves of an unfinished game.
+///
+/// # Examples
+///
+/// ```
+/// use tictacrustle::{
+///     play_match,
+///     RandomAgent,
+/// };
+///
+/// let mut first = RandomAgent::new();
+/// let mut second = RandomAgent::new();
+///
+/// let record = play_match(&mut first, &mut second).unwrap();
+/// assert!(record.moves.len() >= 5);
+/// ```
+pub fn play_match(
+    player_x: &mut dyn Agent,
+    player_o: &mut dyn Agent,
+) -> Result<MatchRecord, GameError> {
+    let mut game = Game::new();
+    let mut moves = Vec::new();
+
+    let outcome = loop {
+        let status = game.status();
+        let GameStatus::InProgress { to_move } = status else {
+            break MatchOutcome::Finished(status);
+        };
+
+        let choice = match to_move {
+            Symbol::X => player_x.choose_move(game.board(), to_move),
+            Symbol::O => player_o.choose_move(game.board(), to_move),
+        };
+        let Some(coord) = choice else {
+            break MatchOutcome::Resigned(to_move);
+        };
+        if let Err(err) = game.play(coord) {
+            let forfeit = MatchOutcome::Resigned(to_move);
+            player_x.on_game_end(&forfeit, Symbol::X);
+            player_o.on_game_end(&forfeit, Symbol::O);
+            return Err(err);
+        }
+        moves.push(coord);
+    };
+
+    player_x.on_game_end(&outcome, Symbol::X);
+    player_o.on_game_end(&outcome, Symbol::O);
+
+    Ok(MatchRecord {
+        outcome,
+        moves,
+        board: *game.board(),
+    })
+}
+
+/// An agent that plays a uniformly random legal move.
+#[derive(Debug, Clone)]
+pub struct RandomAgent {
+    /// The random number generator used to pick moves.
+    rng: SeededRng,
+}
+
+impl RandomAgent {
+    /// Creates a new `RandomAgent` seeded from the operating system.
+    ///
+    /// Use [`Seeded::with_seed`] to make its moves reproducible.
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            rng: SeededRng::from_os_rng(),
+        }
+    }
+}
+
+impl Seeded for RandomAgent {
+    fn rng_mut(&mut self) -> &mut SeededRng {
+        &mut self.rng
+    }
+}
+
+impl Default for RandomAgent {
+    /// Creates a default random agent by calling [`RandomAgent::new`].
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Agent for RandomAgent {
+    fn choose_move(&mut self, board: &Board, _symbol: Symbol) -> Option<Coord> {
+        board.legal_moves().choose(&mut self.rng).copied()
+    }
+}
+
+/// An agent that plays the first legal move from a fixed list of preferences.
+///
+/// This makes a fully predictable opponent for tests and scripted training sessions. If none of
+/// the preferred squares is free, the agent resigns.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScriptedAgent {
+    /// The squares the agent plays, in order of preference.
+    preferences: Vec<Coord>,
+}
+
+impl ScriptedAgent {
+    /// Creates a new `ScriptedAgent`.
+    ///
+    /// # Arguments
+    ///
+    /// * `preferences` - The squares to play, in order of preference.
+    #[must_use]
+    pub const fn new(preferences: Vec<Coord>) -> Self {
+        Self { preferences }
+    }
+}
+
+impl Agent for ScriptedAgent {
+    fn choose_move(&mut self, board: &Board, _symbol: Symbol) -> Option<Coord> {
+        self.preferences
+            .iter()
+            .copied()
+            .find(|&coord| board.square(coord).is_empty())
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use crate::Line;
+
+    fn coords(keys: &[u8]) -> Vec<Coord> {
+        keys.iter()
+            .map(|&key| Coord::from_keypad(key).unwrap())
+            .collect()
+    }
+
+    /// Records the outcomes it is told about.
+    struct Recorder {
+        inner:    ScriptedAgent,
+        outcomes: Vec<(MatchOutcome, Symbol)>,
+    }
+
+    impl Agent for Recorder {
+        fn choose_move(&mut self, board: &Board, symbol: Symbol) -> Option<Coord> {
+            self.inner.choose_move(board, symbol)
+        }
+
+        fn on_game_end(&mut self, outcome: &MatchOutcome, symbol: Symbol) {
+            self.outcomes.push((*outcome, symbol));
+        }
+    }
+
+    #[test]
+    fn test_scripted_match() {
+        let mut player_x = ScriptedAgent::new(coords(&[7, 8, 9]));
+        let mut player_o = Recorder {
+            inner:    ScriptedAgent::new(coords(&[4, 5, 6])),
+            outcomes: Vec::new(),
+        };
+
+        let record = play_match(&mut player_x, &mut player_o).unwrap();
+
+        let expected = MatchOutcome::Finished(GameStatus::Won {
+            symbol: Symbol::X,
+            line:   Line::row(1).unwrap(),
+        });
+        assert_eq!(record.outcome, expected);
+        assert_eq!(record.moves, coords(&[7, 4, 8, 5, 9]));
+        assert_eq!(player_o.outcomes, vec![(expected, Symbol::O)]);
+        assert_eq!(record.outcome.result_for(Symbol::O), GameResult::Loss);
+    }
+
+    #[test]
+    fn test_resignation() {
+        let mut player_x = ScriptedAgent::new(coords(&[5]));
+        let mut player_o = ScriptedAgent::new(coords(&[1, 3]));
+
+        let record = play_match(&mut player_x, &mut player_o).unwrap();
+
+        assert_eq!(record.outcome, MatchOutcome::Resigned(Symbol::X));
+        assert_eq!(record.outcome.winner(), Some(Symbol::O));
+        assert_eq!(record.outcome.result_for(Symbol::X), GameResult::Loss);
+        assert_eq!(record.outcome.result_for(Symbol::O), GameResult::Win);
+    }
+
+    #[test]
+    fn test_random_agents_finish_games() {
+        let mut player_x = RandomAgent::new().with_seed(1);
+        let mut player_o = RandomAgent::new().with_seed(2);
+        for _ in 0..50 {
+            let record = play_match(&mut player_x, &mut player_o).unwrap();
+            assert!(matches!(record.outcome, MatchOutcome::Finished(_)));
+            assert!(record.board.is_reachable());
+        }
+    }
+
+    #[test]
+    fn test_seeded_random_agents_are_reproducible() {
+        let play = |seed: u64| {
+            let mut player_x = RandomAgent::new().with_seed(seed);
+            let mut player_o = RandomAgent::new().with_seed(seed + 1);
+            (0..10)
+                .map(|_| play_match(&mut player_x, &mut player_o).unwrap())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(play(3), play(3));
+        assert_ne!(play(3), play(5));
+    }
+
+    #[test]
+    fn test_illegal_move_is_an_error() {
+        struct Stubborn;
+        impl Agent for Stubborn {
+            fn choose_move(&mut self, _board: &Board, _symbol: Symbol) -> Option<Coord> {
+                Coord::new(2, 2).ok()
+            }
+        }
+
+        let result = play_match(&mut Stubborn, &mut Stubborn);
+        assert_eq!(result, Err(GameError::SquareAlreadyX));
+
+        let mut player_x = Recorder {
+            inner:    ScriptedAgent::new(coords(&[5, 1])),
+            outcomes: Vec::new(),
+        };
+        let result = play_match(&mut player_x, &mut Stubborn);
+        assert_eq!(result, Err(GameError::SquareAlreadyX));
+        assert_eq!(
+            player_x.outcomes,
+            vec![(MatchOutcome::Resigned(Symbol::O), Symbol::X)]
+        );
+    }
+}
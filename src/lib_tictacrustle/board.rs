@@ -5,13 +5,24 @@
 
 use std::fmt::Display;
 
-use crate::Square;
+use crate::{
+    BoardCodec,
+    Coord,
+    GameError,
+    GameStatus,
+    Line,
+    Square,
+    SquareValue,
+    Symbol,
+    Transform,
+    WinningLine,
+};
 
 /// Represents the 3x3 grid for a Tic Tac Toe game.
 ///
 /// Internally, the board stores 9 `Square` values in a flat array.
 /// Squares are accessed using 1-based row and column coordinates (1 to 3).
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct Board {
     squares: [Square; 9],
 }
@@ -60,6 +71,65 @@ impl Board {
         &mut self.squares[index]
     }
 
+    /// Retrieves an immutable reference to a square at the specified row and column, without
+    /// panicking on invalid input.
+    ///
+    /// # Arguments
+    ///
+    /// * `row` - Row number (1-based, valid values: 1 to 3).
+    /// * `col` - Column number (1-based, valid values: 1 to 3).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`GameError::InvalidSquare`] if row or column are outside the range 1..=3.
+    pub const fn try_get_square(&self, row: usize, col: usize) -> Result<&Square, GameError> {
+        match Coord::new(row, col) {
+            Ok(coord) => Ok(self.square(coord)),
+            Err(error) => Err(error),
+        }
+    }
+
+    /// Retrieves a mutable reference to a square at the specified row and column, without
+    /// panicking on invalid input.
+    ///
+    /// # Arguments
+    ///
+    /// * `row` - Row number (1-based, valid values: 1 to 3).
+    /// * `col` - Column number (1-based, valid values: 1 to 3).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`GameError::InvalidSquare`] if row or column are outside the range 1..=3.
+    pub const fn try_get_square_mut(
+        &mut self,
+        row: usize,
+        col: usize,
+    ) -> Result<&mut Square, GameError> {
+        match Coord::new(row, col) {
+            Ok(coord) => Ok(self.square_mut(coord)),
+            Err(error) => Err(error),
+        }
+    }
+
+    /// Retrieves an immutable reference to the square at a validated coordinate.
+    ///
+    /// # Arguments
+    ///
+    /// * `coord` - The position of the square.
+    #[must_use]
+    pub const fn square(&self, coord: Coord) -> &Square {
+        &self.squares[coord.index()]
+    }
+
+    /// Retrieves a mutable reference to the square at a validated coordinate.
+    ///
+    /// # Arguments
+    ///
+    /// * `coord` - The position of the square.
+    pub const fn square_mut(&mut self, coord: Coord) -> &mut Square {
+        &mut self.squares[coord.index()]
+    }
+
     /// Retrieves an array of references to the squares in the specified row.
     ///
     /// # Arguments
@@ -136,6 +206,392 @@ impl Board {
         }
     }
 
+    /// Retrieves an array of references to the squares in the specified row, without panicking
+    /// on invalid input.
+    ///
+    /// # Arguments
+    ///
+    /// * `row` - Row number (1-based, valid values: 1 to 3).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`GameError::InvalidSquare`] if row is outside the range 1..=3.
+    pub fn try_get_row(&self, row: usize) -> Result<[&Square; 3], GameError> {
+        Ok(self.get_line(Line::row(row)?))
+    }
+
+    /// Retrieves an array of references to the squares in the specified column, without
+    /// panicking on invalid input.
+    ///
+    /// # Arguments
+    ///
+    /// * `col` - Column number (1-based, valid values: 1 to 3).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`GameError::InvalidSquare`] if column is outside the range 1..=3.
+    pub fn try_get_column(&self, col: usize) -> Result<[&Square; 3], GameError> {
+        Ok(self.get_line(Line::column(col)?))
+    }
+
+    /// Retrieves an array of references to the squares on one of the two diagonals, without
+    /// panicking on invalid input.
+    ///
+    /// # Arguments
+    ///
+    /// * `diagonal` - `"l"` for the left-to-right diagonal or `"r"` for the right-to-left diagonal.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`GameError::InvalidSquare`] if `diagonal` is not `"l"` or `"r"`.
+    pub fn try_get_diagonal(&self, diagonal: &str) -> Result<[&Square; 3], GameError> {
+        match diagonal {
+            "l" => Ok(self.get_line(Line::MainDiagonal)),
+            "r" => Ok(self.get_line(Line::AntiDiagonal)),
+            _ => Err(GameError::InvalidSquare),
+        }
+    }
+
+    /// Retrieves an array of references to the squares on the specified line.
+    ///
+    /// # Arguments
+    ///
+    /// * `line` - The row, column, or diagonal to return.
+    ///
+    /// # Returns
+    ///
+    /// A fixed-size array containing references to the 3 squares on the line.
+    #[must_use]
+    pub fn get_line(&self, line: Line) -> [&Square; 3] {
+        line.coords().map(|coord| self.square(coord))
+    }
+
+    /// Counts the squares holding the specified value.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The `SquareValue` to count.
+    ///
+    /// # Returns
+    ///
+    /// The number of squares on the board holding `value`.
+    #[must_use]
+    pub fn count(&self, value: SquareValue) -> usize {
+        self.squares
+            .iter()
+            .filter(|square| square.get_value() == value)
+            .count()
+    }
+
+    /// Checks whether every square on the board has been played.
+    #[must_use]
+    pub fn is_full(&self) -> bool {
+        self.count(SquareValue::Empty) == 0
+    }
+
+    /// Returns the symbol of the player who moves next, assuming `X` always moves first.
+    ///
+    /// # Returns
+    ///
+    /// `Symbol::X` if both players have played the same number of moves, `Symbol::O` otherwise.
+    #[must_use]
+    pub fn to_move(&self) -> Symbol {
+        if self.count(SquareValue::X) > self.count(SquareValue::O) {
+            Symbol::O
+        } else {
+            Symbol::X
+        }
+    }
+
+    /// Determines the status of the game represented by this board.
+    ///
+    /// This is a pure function of the squares on the board: it does not depend on how the
+    /// position was reached. On an illegal board where both players have completed a line, the
+    /// first line found by [`Board::winning_lines`] decides the winner.
+    ///
+    /// # Returns
+    ///
+    /// - [`GameStatus::Won`] if a player has completed a line.
+    /// - [`GameStatus::Draw`] if the board is full and no player has won.
+    /// - [`GameStatus::InProgress`] otherwise, along with the player who moves next.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tictacrustle::{
+    ///     Board,
+    ///     GameStatus,
+    ///     Line,
+    ///     Symbol,
+    /// };
+    ///
+    /// let mut board = Board::new();
+    /// for col in 1..=3 {
+    ///     board.get_square_mut(1, col).set_x();
+    /// }
+    /// board.get_square_mut(2, 1).set_o();
+    /// board.get_square_mut(2, 2).set_o();
+    ///
+    /// assert_eq!(
+    ///     board.status(),
+    ///     GameStatus::Won {
+    ///         symbol: Symbol::X,
+    ///         line:   Line::row(1).unwrap(),
+    ///     }
+    /// );
+    /// ```
+    #[must_use]
+    pub fn status(&self) -> GameStatus {
+        let winner = self
+            .winning_lines()
+            .into_iter()
+            .find_map(|winning_line| Some((winning_line.line, winning_line.owner.symbol()?)));
+
+        if let Some((line, symbol)) = winner {
+            GameStatus::Won { symbol, line }
+        } else if self.is_full() {
+            GameStatus::Draw
+        } else {
+            GameStatus::InProgress {
+                to_move: self.to_move(),
+            }
+        }
+    }
+
+    /// Finds a complete line on the board, along with the value of the squares that own it.
+    ///
+    /// Lines are checked in the order of [`Line::ALL`] using [`Board::get_line`]. If a single
+    /// player completes two lines with one move, the first of them is returned; use
+    /// [`Board::winning_lines`] to get all of them.
+    ///
+    /// # Returns
+    ///
+    /// `Some(WinningLine)` if a player has completed a line, `None` otherwise.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`GameError::BothPlayersWon`] if both `X` and `O` have completed a line, which
+    /// cannot happen in a legal game.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tictacrustle::{
+    ///     Board,
+    ///     Line,
+    ///     SquareValue,
+    /// };
+    ///
+    /// let mut board = Board::new();
+    /// for row in 1..=3 {
+    ///     board.get_square_mut(row, 2).set_o();
+    /// }
+    ///
+    /// let winning_line = board.winning_line().unwrap().unwrap();
+    /// assert_eq!(winning_line.line, Line::column(2).unwrap());
+    /// assert_eq!(winning_line.owner, SquareValue::O);
+    /// ```
+    pub fn winning_line(&self) -> Result<Option<WinningLine>, GameError> {
+        let lines = self.winning_lines();
+        match lines.first() {
+            Some(first) if lines.iter().any(|other| other.owner != first.owner) => {
+                Err(GameError::BothPlayersWon)
+            }
+            first => Ok(first.copied()),
+        }
+    }
+
+    /// Finds every complete line on the board.
+    ///
+    /// # Returns
+    ///
+    /// A vector of all complete lines, in the order of [`Line::ALL`]. The vector is empty if no
+    /// player has completed a line.
+    #[must_use]
+    pub fn winning_lines(&self) -> Vec<WinningLine> {
+        Line::ALL
+            .into_iter()
+            .filter_map(|line| match self.get_line(line).map(Square::get_value) {
+                [SquareValue::Empty, ..] => None,
+                [first, second, third] if first == second && second == third => {
+                    Some(WinningLine { line, owner: first })
+                }
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Returns the number of moves that have been played on the board.
+    #[must_use]
+    pub fn ply(&self) -> usize {
+        9 - self.count(SquareValue::Empty)
+    }
+
+    /// Returns the squares the player to move may play.
+    ///
+    /// # Returns
+    ///
+    /// The empty squares in row-major order, or an empty vector if the game is already over.
+    #[must_use]
+    pub fn legal_moves(&self) -> Vec<Coord> {
+        if self.status().is_over() {
+            return Vec::new();
+        }
+        Coord::all()
+            .filter(|&coord| self.square(coord).is_empty())
+            .collect()
+    }
+
+    /// Returns the empty squares that would complete a line for the given player.
+    ///
+    /// The player does not need to be the one to move, so this also finds the squares a player
+    /// must block to stop their opponent winning.
+    ///
+    /// # Arguments
+    ///
+    /// * `symbol` - The player whose winning squares are wanted.
+    ///
+    /// # Returns
+    ///
+    /// The winning squares in row-major order without duplicates, or an empty vector if the game
+    /// is already over.
+    #[must_use]
+    pub fn winning_moves(&self, symbol: Symbol) -> Vec<Coord> {
+        let owner = SquareValue::from(symbol);
+        self.legal_moves()
+            .into_iter()
+            .filter(|&coord| {
+                Line::ALL.into_iter().any(|line| {
+                    let coords = line.coords();
+                    coords.contains(&coord)
+                        && coords
+                            .iter()
+                            .filter(|&&other| other != coord)
+                            .all(|&other| self.square(other).get_value() == owner)
+                })
+            })
+            .collect()
+    }
+
+    /// Returns the board that results from the player to move marking the given square.
+    ///
+    /// The board itself is left unchanged, which makes this convenient for searching the game
+    /// tree.
+    ///
+    /// # Arguments
+    ///
+    /// * `coord` - The square to mark with the symbol of the player to move.
+    ///
+    /// # Errors
+    ///
+    /// - [`GameError::GameAlreadyWon`] if a player has already completed a line.
+    /// - [`GameError::SquareAlreadyX`] or [`GameError::SquareAlreadyO`] if the square is taken.
+    pub fn after_move(&self, coord: Coord) -> Result<Self, GameError> {
+        if self.status().winner().is_some() {
+            return Err(GameError::GameAlreadyWon);
+        }
+        match self.square(coord).get_value() {
+            SquareValue::X => return Err(GameError::SquareAlreadyX),
+            SquareValue::O => return Err(GameError::SquareAlreadyO),
+            SquareValue::Empty => {}
+        }
+
+        let mut next = *self;
+        match self.to_move() {
+            Symbol::X => next.square_mut(coord).set_x(),
+            Symbol::O => next.square_mut(coord).set_o(),
+        }
+        Ok(next)
+    }
+
+    /// Checks that the board can arise in a legal game where `X` moves first.
+    ///
+    /// # Errors
+    ///
+    /// - [`GameError::UnreachablePosition`] if the number of `X` and `O` squares cannot occur in a
+    ///   legal game, or if a player kept playing after the game was won.
+    /// - [`GameError::BothPlayersWon`] if both players have completed a line.
+    pub fn validate(&self) -> Result<(), GameError> {
+        let x_count = self.count(SquareValue::X);
+        let o_count = self.count(SquareValue::O);
+        if x_count != o_count && x_count != o_count + 1 {
+            return Err(GameError::UnreachablePosition);
+        }
+
+        match self.winning_line()?.and_then(|line| line.owner.symbol()) {
+            Some(Symbol::X) if x_count != o_count + 1 => Err(GameError::UnreachablePosition),
+            Some(Symbol::O) if x_count != o_count => Err(GameError::UnreachablePosition),
+            _ => Ok(()),
+        }
+    }
+
+    /// Checks whether the board can arise in a legal game where `X` moves first.
+    ///
+    /// This is useful for rejecting positions uploaded by clients before they are analysed.
+    /// See [`Board::validate`] for the reason a board is rejected.
+    #[must_use]
+    pub fn is_reachable(&self) -> bool {
+        self.validate().is_ok()
+    }
+
+    /// Returns the image of the board under one of the eight board symmetries.
+    ///
+    /// # Arguments
+    ///
+    /// * `transform` - The rotation or reflection to apply.
+    ///
+    /// # Returns
+    ///
+    /// A new board where the square at `coord` on this board has moved to
+    /// `coord.transform(transform)`.
+    #[must_use]
+    pub fn transform(&self, transform: Transform) -> Self {
+        let mut transformed = Self::new();
+        for coord in Coord::all() {
+            *transformed.square_mut(coord.transform(transform)) = *self.square(coord);
+        }
+        transformed
+    }
+
+    /// Finds the canonical representative of the board among its eight symmetric images.
+    ///
+    /// The canonical board is the image with the smallest [`BoardCodec::to_number`], so all
+    /// boards that are rotations or reflections of each other share the same canonical form.
+    /// A move chosen on the canonical board maps back to this board with the inverse transform.
+    ///
+    /// # Returns
+    ///
+    /// A tuple of the canonical board and the transform that produces it from this board.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tictacrustle::{
+    ///     Board,
+    ///     Coord,
+    /// };
+    ///
+    /// let mut board = Board::new();
+    /// board.get_square_mut(1, 1).set_x();
+    ///
+    /// let (canonical, transform) = board.canonical();
+    /// assert_eq!(board.transform(transform), canonical);
+    ///
+    /// // A move on the canonical board maps back to the real board.
+    /// let canonical_move = Coord::new(2, 2).unwrap();
+    /// let real_move = canonical_move.transform(transform.inverse());
+    /// assert!(board.square(real_move).is_empty());
+    /// ```
+    #[must_use]
+    pub fn canonical(&self) -> (Self, Transform) {
+        let codec = BoardCodec::new();
+        Transform::ALL
+            .into_iter()
+            .map(|transform| (self.transform(transform), transform))
+            .min_by_key(|(board, _)| codec.to_number(board))
+            .unwrap_or((*self, Transform::Identity))
+    }
+
     /// Converts 1-based `(row, col)` coordinates to a 0-based index in the internal square array.
     ///
     /// # Arguments
@@ -195,6 +651,8 @@ impl Display for Board {
 
 #[cfg(test)]
 mod tests {
+    use rstest::rstest;
+
     use super::*;
 
     #[test]
@@ -219,4 +677,263 @@ mod tests {
         assert_eq!(board.get_diagonal("l"), [&Square::default(); 3]);
         assert_eq!(board.get_diagonal("r"), [&Square::default(); 3]);
     }
+
+    #[test]
+    fn test_get_line() {
+        let mut board = Board::default();
+        board.get_square_mut(1, 3).set_x();
+        board.get_square_mut(3, 1).set_o();
+
+        assert_eq!(board.get_line(Line::row(1).unwrap()), board.get_row(1));
+        assert_eq!(
+            board.get_line(Line::column(1).unwrap()),
+            board.get_column(1)
+        );
+        assert_eq!(board.get_line(Line::MainDiagonal), board.get_diagonal("l"));
+        assert_eq!(board.get_line(Line::AntiDiagonal), board.get_diagonal("r"));
+    }
+
+    #[test]
+    fn test_try_get_square() {
+        let mut board = Board::default();
+        board.try_get_square_mut(3, 2).unwrap().set_o();
+
+        assert_eq!(board.try_get_square(3, 2), Ok(board.get_square(3, 2)));
+        assert!(board.try_get_square(3, 2).unwrap().is_o());
+        assert_eq!(board.try_get_square(0, 2), Err(GameError::InvalidSquare));
+        assert_eq!(
+            board.try_get_square_mut(2, 4),
+            Err(GameError::InvalidSquare)
+        );
+    }
+
+    #[test]
+    fn test_try_get_lines() {
+        let board = Board::default();
+        assert_eq!(board.try_get_row(1), Ok(board.get_row(1)));
+        assert_eq!(board.try_get_column(3), Ok(board.get_column(3)));
+        assert_eq!(board.try_get_diagonal("l"), Ok(board.get_diagonal("l")));
+        assert_eq!(board.try_get_diagonal("r"), Ok(board.get_diagonal("r")));
+
+        assert_eq!(board.try_get_row(0), Err(GameError::InvalidSquare));
+        assert_eq!(board.try_get_column(4), Err(GameError::InvalidSquare));
+        assert_eq!(board.try_get_diagonal("x"), Err(GameError::InvalidSquare));
+    }
+
+    #[test]
+    fn test_square_by_coord() {
+        let mut board = Board::default();
+        let coord = Coord::from_keypad(9).unwrap();
+        board.square_mut(coord).set_x();
+        assert!(board.get_square(1, 3).is_x());
+        assert!(board.square(coord).is_x());
+    }
+
+    #[test]
+    fn test_legal_moves() {
+        let mut board = Board::default();
+        assert_eq!(board.legal_moves().len(), 9);
+
+        board = board.after_move(Coord::new(2, 2).unwrap()).unwrap();
+        assert_eq!(board.ply(), 1);
+        assert_eq!(board.legal_moves().len(), 8);
+        assert!(!board.legal_moves().contains(&Coord::new(2, 2).unwrap()));
+    }
+
+    #[rstest]
+    #[case("110220000", Symbol::X, vec![(1, 3)])]
+    #[case("110220000", Symbol::O, vec![(2, 3)])]
+    #[case("101020000", Symbol::X, vec![(1, 2)])]
+    #[case("100020001", Symbol::O, vec![])]
+    #[case("120120000", Symbol::X, vec![(3, 1)])]
+    #[case("111220000", Symbol::O, vec![])]
+    fn test_winning_moves(
+        #[case] encoded: &str,
+        #[case] symbol: Symbol,
+        #[case] expected: Vec<(usize, usize)>,
+    ) {
+        let board = BoardCodec::new().decode(encoded).unwrap();
+        let expected: Vec<Coord> = expected
+            .into_iter()
+            .map(|(row, col)| Coord::new(row, col).unwrap())
+            .collect();
+        assert_eq!(board.winning_moves(symbol), expected);
+    }
+
+    #[test]
+    fn test_after_move() {
+        let board = Board::default();
+        let centre = Coord::new(2, 2).unwrap();
+
+        let next = board.after_move(centre).unwrap();
+        assert!(next.square(centre).is_x());
+        assert!(board.square(centre).is_empty());
+        assert_eq!(next.after_move(centre), Err(GameError::SquareAlreadyX));
+
+        let next = next.after_move(Coord::new(1, 1).unwrap()).unwrap();
+        assert!(next.get_square(1, 1).is_o());
+    }
+
+    #[test]
+    fn test_after_move_rejects_finished_game() {
+        let codec = BoardCodec::new();
+        let board = codec.decode("111220000").unwrap();
+        assert!(board.legal_moves().is_empty());
+        assert_eq!(
+            board.after_move(Coord::new(3, 3).unwrap()),
+            Err(GameError::GameAlreadyWon)
+        );
+    }
+
+    #[test]
+    fn test_validate() {
+        let codec = BoardCodec::new();
+        let validate = |encoded: &str| codec.decode(encoded).unwrap().validate();
+
+        assert_eq!(validate("000000000"), Ok(()));
+        assert_eq!(validate("111220000"), Ok(()));
+        assert_eq!(validate("222110100"), Ok(()));
+        assert_eq!(validate("200000000"), Err(GameError::UnreachablePosition));
+        assert_eq!(validate("110000000"), Err(GameError::UnreachablePosition));
+        assert_eq!(validate("111222100"), Err(GameError::BothPlayersWon));
+        assert_eq!(validate("111220200"), Err(GameError::UnreachablePosition));
+        assert_eq!(validate("222110110"), Err(GameError::UnreachablePosition));
+    }
+
+    #[test]
+    fn test_transform_moves_squares() {
+        let mut board = Board::default();
+        board.get_square_mut(1, 1).set_x();
+        board.get_square_mut(1, 2).set_o();
+
+        let rotated = board.transform(Transform::Rotate90);
+        assert!(rotated.get_square(1, 3).is_x());
+        assert!(rotated.get_square(2, 3).is_o());
+        assert_eq!(rotated.transform(Transform::Rotate270), board);
+    }
+
+    #[test]
+    fn test_canonical_is_shared_by_symmetric_boards() {
+        let mut board = Board::default();
+        board.get_square_mut(1, 2).set_x();
+        board.get_square_mut(3, 3).set_o();
+
+        let (canonical, transform) = board.canonical();
+        assert_eq!(board.transform(transform), canonical);
+        for symmetry in Transform::ALL {
+            assert_eq!(board.transform(symmetry).canonical().0, canonical);
+        }
+    }
+
+    #[test]
+    fn test_status_empty_board() {
+        let board = Board::default();
+        assert_eq!(
+            board.status(),
+            GameStatus::InProgress { to_move: Symbol::X }
+        );
+    }
+
+    #[test]
+    fn test_status_o_to_move() {
+        let mut board = Board::default();
+        board.get_square_mut(2, 2).set_x();
+        assert_eq!(board.to_move(), Symbol::O);
+        assert_eq!(
+            board.status(),
+            GameStatus::InProgress { to_move: Symbol::O }
+        );
+    }
+
+    #[test]
+    fn test_status_won() {
+        let mut board = Board::default();
+        board.get_square_mut(1, 3).set_o();
+        board.get_square_mut(2, 2).set_o();
+        board.get_square_mut(3, 1).set_o();
+        board.get_square_mut(1, 1).set_x();
+        board.get_square_mut(1, 2).set_x();
+        board.get_square_mut(3, 3).set_x();
+        assert_eq!(
+            board.status(),
+            GameStatus::Won {
+                symbol: Symbol::O,
+                line:   Line::AntiDiagonal,
+            }
+        );
+    }
+
+    #[test]
+    fn test_status_draw() {
+        let mut board = Board::default();
+        for (row, col) in [(1, 1), (1, 2), (2, 3), (3, 1), (3, 3)] {
+            board.get_square_mut(row, col).set_x();
+        }
+        for (row, col) in [(1, 3), (2, 1), (2, 2), (3, 2)] {
+            board.get_square_mut(row, col).set_o();
+        }
+        assert!(board.is_full());
+        assert_eq!(board.status(), GameStatus::Draw);
+        assert_eq!(board.winning_line(), Ok(None));
+    }
+
+    #[test]
+    fn test_winning_line_none_on_empty_board() {
+        let board = Board::default();
+        assert_eq!(board.winning_line(), Ok(None));
+        assert!(board.winning_lines().is_empty());
+    }
+
+    #[test]
+    fn test_winning_line_for_every_line() {
+        for line in Line::ALL {
+            let mut board = Board::default();
+            let coordinates: Vec<(usize, usize)> = (1..=3)
+                .flat_map(|row| (1..=3).map(move |col| (row, col)))
+                .filter(|&(row, col)| match line {
+                    Line::Row(n) => row == n.get(),
+                    Line::Column(n) => col == n.get(),
+                    Line::MainDiagonal => row == col,
+                    Line::AntiDiagonal => row + col == 4,
+                })
+                .collect();
+            for (row, col) in coordinates {
+                board.get_square_mut(row, col).set_x();
+            }
+
+            assert_eq!(
+                board.winning_line(),
+                Ok(Some(WinningLine {
+                    line,
+                    owner: SquareValue::X,
+                }))
+            );
+        }
+    }
+
+    #[test]
+    fn test_winning_lines_double_win() {
+        let mut board = Board::default();
+        for (row, col) in [(1, 1), (1, 2), (1, 3), (2, 1), (3, 1)] {
+            board.get_square_mut(row, col).set_x();
+        }
+        for (row, col) in [(2, 2), (2, 3), (3, 2), (3, 3)] {
+            board.get_square_mut(row, col).set_o();
+        }
+
+        let lines: Vec<Line> = board.winning_lines().iter().map(|w| w.line).collect();
+        let (row, column) = (Line::row(1).unwrap(), Line::column(1).unwrap());
+        assert_eq!(lines, vec![row, column]);
+        assert_eq!(board.winning_line().unwrap().map(|w| w.line), Some(row));
+    }
+
+    #[test]
+    fn test_winning_line_rejects_two_winners() {
+        let mut board = Board::default();
+        for col in 1..=3 {
+            board.get_square_mut(1, col).set_x();
+            board.get_square_mut(3, col).set_o();
+        }
+        assert_eq!(board.winning_line(), Err(GameError::BothPlayersWon));
+    }
 }
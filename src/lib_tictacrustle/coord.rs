@@ -0,0 +1,285 @@
+// SPDX-FileCopyrightText: 2022 - 2024 Ali Sajid Imami
+//
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use std::fmt;
+
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+use crate::{
+    GameError,
+    LineIndex,
+    Transform,
+};
+
+/// A validated position of a square on the 3x3 board.
+///
+/// A `Coord` can only be created for rows and columns in the range 1..=3, so any API that takes a
+/// `Coord` cannot be handed an out-of-range square. Coordinates can be converted to and from
+/// three numbering schemes:
+///
+/// - 1-based `(row, col)` pairs, as used by [`Board::get_square`](crate::Board::get_square).
+/// - 0-based indices (0 to 8) in row-major order, starting at the top-left square.
+/// - Numeric keypad keys (1 to 9), where `7 8 9` is the top row and `1 2 3` is the bottom row.
+///
+/// # Examples
+///
+/// ```
+/// use tictacrustle::Coord;
+///
+/// let centre = Coord::new(2, 2).unwrap();
+/// assert_eq!(centre.index(), 4);
+/// assert_eq!(centre.keypad(), 5);
+/// assert_eq!(Coord::from_keypad(7).unwrap(), Coord::new(1, 1).unwrap());
+/// assert!(Coord::new(0, 1).is_err());
+/// ```
+///
+/// In JSON a `Coord` is written as `{"row": 1, "col": 3}`, and out-of-range values are rejected
+/// when it is read.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(try_from = "RawCoord", into = "RawCoord")]
+pub struct Coord {
+    /// Row number (1-based).
+    row: usize,
+    /// Column number (1-based).
+    col: usize,
+}
+
+impl Coord {
+    /// Creates a new `Coord` from 1-based row and column numbers.
+    ///
+    /// # Arguments
+    ///
+    /// * `row` - Row number (1-based, valid values: 1 to 3).
+    /// * `col` - Column number (1-based, valid values: 1 to 3).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`GameError::InvalidSquare`] if row or column are outside the range 1..=3.
+    pub const fn new(row: usize, col: usize) -> Result<Self, GameError> {
+        if row < 1 || row > 3 || col < 1 || col > 3 {
+            return Err(GameError::InvalidSquare);
+        }
+        Ok(Self { row, col })
+    }
+
+    /// Creates a new `Coord` from row and column numbers that have already been validated.
+    ///
+    /// # Arguments
+    ///
+    /// * `row` - Row number.
+    /// * `col` - Column number.
+    #[must_use]
+    pub const fn from_line_indices(row: LineIndex, col: LineIndex) -> Self {
+        Self {
+            row: row.get(),
+            col: col.get(),
+        }
+    }
+
+    /// Creates a new `Coord` from a 0-based index in row-major order.
+    ///
+    /// # Arguments
+    ///
+    /// * `index` - Index of the square (valid values: 0 to 8).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`GameError::InvalidSquare`] if the index is greater than 8.
+    pub const fn from_index(index: usize) -> Result<Self, GameError> {
+        if index > 8 {
+            return Err(GameError::InvalidSquare);
+        }
+        Ok(Self {
+            row: index / 3 + 1,
+            col: index % 3 + 1,
+        })
+    }
+
+    /// Creates a new `Coord` from a numeric keypad key.
+    ///
+    /// The keypad layout mirrors the board: `7 8 9` is the top row, `4 5 6` the middle row and
+    /// `1 2 3` the bottom row.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - Keypad key (valid values: 1 to 9).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`GameError::InvalidSquare`] if the key is outside the range 1..=9.
+    pub const fn from_keypad(key: u8) -> Result<Self, GameError> {
+        if key < 1 || key > 9 {
+            return Err(GameError::InvalidSquare);
+        }
+        let offset = (key - 1) as usize;
+        Ok(Self {
+            row: 3 - offset / 3,
+            col: offset % 3 + 1,
+        })
+    }
+
+    /// Returns an iterator over all nine squares of the board, in row-major order.
+    pub fn all() -> impl Iterator<Item = Self> {
+        (0..9).map(|index| Self {
+            row: index / 3 + 1,
+            col: index % 3 + 1,
+        })
+    }
+
+    /// Returns the 1-based row number.
+    #[must_use]
+    pub const fn row(self) -> usize {
+        self.row
+    }
+
+    /// Returns the 1-based column number.
+    #[must_use]
+    pub const fn col(self) -> usize {
+        self.col
+    }
+
+    /// Returns the 0-based index of the square in row-major order.
+    #[must_use]
+    pub const fn index(self) -> usize {
+        (self.row - 1) * 3 + (self.col - 1)
+    }
+
+    /// Maps the square to its image under one of the board symmetries.
+    ///
+    /// # Arguments
+    ///
+    /// * `transform` - The rotation or reflection to apply.
+    ///
+    /// # Returns
+    ///
+    /// The position the square moves to when the whole board is transformed.
+    #[must_use]
+    pub const fn transform(self, transform: Transform) -> Self {
+        let (row, col) = (self.row, self.col);
+        let (row, col) = match transform {
+            Transform::Identity => (row, col),
+            Transform::Rotate90 => (col, 4 - row),
+            Transform::Rotate180 => (4 - row, 4 - col),
+            Transform::Rotate270 => (4 - col, row),
+            Transform::FlipHorizontal => (row, 4 - col),
+            Transform::FlipVertical => (4 - row, col),
+            Transform::Transpose => (col, row),
+            Transform::AntiTranspose => (4 - col, 4 - row),
+        };
+        Self { row, col }
+    }
+
+    /// Returns the numeric keypad key for the square.
+    #[must_use]
+    #[allow(clippy::cast_possible_truncation)]
+    pub const fn keypad(self) -> u8 {
+        ((3 - self.row) * 3 + self.col) as u8
+    }
+}
+
+impl fmt::Display for Coord {
+    /// Formats the coordinate as `(row, col)`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "({}, {})", self.row, self.col)
+    }
+}
+
+/// The unvalidated form of a [`Coord`] used for serialisation.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+struct RawCoord {
+    /// Row number (1-based).
+    row: usize,
+    /// Column number (1-based).
+    col: usize,
+}
+
+impl TryFrom<RawCoord> for Coord {
+    type Error = GameError;
+
+    fn try_from(raw: RawCoord) -> Result<Self, Self::Error> {
+        Self::new(raw.row, raw.col)
+    }
+}
+
+impl From<Coord> for RawCoord {
+    fn from(coord: Coord) -> Self {
+        Self {
+            row: coord.row,
+            col: coord.col,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::*;
+
+    #[rstest]
+    #[case(0, 1)]
+    #[case(1, 0)]
+    #[case(4, 1)]
+    #[case(1, 4)]
+    fn test_new_rejects_out_of_range(#[case] row: usize, #[case] col: usize) {
+        assert_eq!(Coord::new(row, col), Err(GameError::InvalidSquare));
+    }
+
+    #[test]
+    fn test_index_round_trip() {
+        for index in 0..9 {
+            assert_eq!(Coord::from_index(index).unwrap().index(), index);
+        }
+        assert_eq!(Coord::from_index(9), Err(GameError::InvalidSquare));
+    }
+
+    #[test]
+    fn test_keypad_round_trip() {
+        for key in 1..=9 {
+            assert_eq!(Coord::from_keypad(key).unwrap().keypad(), key);
+        }
+        assert_eq!(Coord::from_keypad(0), Err(GameError::InvalidSquare));
+        assert_eq!(Coord::from_keypad(10), Err(GameError::InvalidSquare));
+    }
+
+    #[rstest]
+    #[case(7, 1, 1)]
+    #[case(9, 1, 3)]
+    #[case(5, 2, 2)]
+    #[case(1, 3, 1)]
+    #[case(3, 3, 3)]
+    fn test_keypad_layout(#[case] key: u8, #[case] row: usize, #[case] col: usize) {
+        assert_eq!(
+            Coord::from_keypad(key).unwrap(),
+            Coord::new(row, col).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_all() {
+        let all: Vec<Coord> = Coord::all().collect();
+        assert_eq!(all.len(), 9);
+        assert_eq!(all[0], Coord::new(1, 1).unwrap());
+        assert_eq!(all[8], Coord::new(3, 3).unwrap());
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!(Coord::new(3, 1).unwrap().to_string(), "(3, 1)");
+    }
+
+    #[test]
+    fn test_json() {
+        let coord = Coord::new(1, 3).unwrap();
+        let json = serde_json::to_string(&coord).unwrap();
+        assert_eq!(json, r#"{"row":1,"col":3}"#);
+        assert_eq!(serde_json::from_str::<Coord>(&json).unwrap(), coord);
+        assert!(serde_json::from_str::<Coord>(r#"{"row":0,"col":3}"#).is_err());
+        assert!(serde_json::from_str::<Coord>(r#"{"row":1,"col":4}"#).is_err());
+    }
+}
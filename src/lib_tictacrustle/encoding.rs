@@ -1,51 +1,207 @@
-struct Encoding;
+// SPDX-FileCopyrightText: 2022 - 2024 Ali Sajid Imami
+//
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
 
-impl Encoding {
-    pub fn new() -> Self {
+use crate::{
+    Board,
+    Coord,
+    GameError,
+    SquareValue,
+};
+
+/// The number of distinct boards that can be encoded, `3^9`.
+const BOARD_COUNT: u16 = 19_683;
+
+/// Encodes and decodes boards to the compact formats used to key MENACE matchboxes.
+///
+/// Two formats are supported, both of which walk the squares in row-major order starting at the
+/// top-left square:
+///
+/// - A 9 character string, where `0` is an empty square, `1` is `X` and `2` is `O`.
+/// - The same digits read as a base-3 number, which always fits in a `u16`.
+///
+/// # Examples
+///
+/// ```
+/// use tictacrustle::{
+///     Board,
+///     BoardCodec,
+/// };
+///
+/// let codec = BoardCodec::new();
+/// let mut board = Board::new();
+/// board.get_square_mut(1, 1).set_x();
+/// board.get_square_mut(2, 2).set_o();
+///
+/// assert_eq!(codec.encode(&board), "100020000");
+/// assert_eq!(codec.to_number(&board), 6561 + 2 * 81);
+/// assert_eq!(codec.decode("100020000").unwrap(), board);
+/// assert_eq!(codec.from_number(6561 + 2 * 81).unwrap(), board);
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BoardCodec;
+
+impl BoardCodec {
+    /// Creates a new `BoardCodec`.
+    #[must_use]
+    pub const fn new() -> Self {
         Self
     }
 
+    /// Encodes a board as a 9 character string of `0`, `1` and `2` digits.
+    ///
+    /// # Arguments
+    ///
+    /// * `board` - The board to encode.
+    ///
+    /// # Returns
+    ///
+    /// The encoded board, one digit per square in row-major order.
+    #[must_use]
     pub fn encode(&self, board: &Board) -> String {
-        let mut encoded = String::new();
-        for row in board.iter() {
-            for cell in row.iter() {
-                encoded.push(match cell {
-                    Cell::Empty => '0',
-                    Cell::X => '1',
-                    Cell::O => '2',
-                });
-            }
-        }
-        encoded
+        Coord::all()
+            .map(|coord| match board.square(coord).get_value() {
+                SquareValue::Empty => '0',
+                SquareValue::X => '1',
+                SquareValue::O => '2',
+            })
+            .collect()
     }
 
-    pub fn decode(&self, encoded: &str) -> Board {
+    /// Decodes a board from a 9 character string of `0`, `1` and `2` digits.
+    ///
+    /// # Arguments
+    ///
+    /// * `encoded` - The encoded board, as produced by [`BoardCodec::encode`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`GameError::InvalidEncoding`] if the string is not exactly 9 characters long or
+    /// contains a character other than `0`, `1` or `2`.
+    pub fn decode(&self, encoded: &str) -> Result<Board, GameError> {
+        if encoded.chars().count() != 9 {
+            return Err(GameError::InvalidEncoding);
+        }
+
         let mut board = Board::new();
-        for (i, c) in encoded.chars().enumerate() {
-            let row = i / 3;
-            let col = i % 3;
-            board[row][col] = match c {
-                '0' => Cell::Empty,
-                '1' => Cell::X,
-                '2' => Cell::O,
-                _ => panic!("Invalid character in encoded string"),
-            };
+        for (coord, c) in Coord::all().zip(encoded.chars()) {
+            let square = board.square_mut(coord);
+            match c {
+                '0' => square.set_empty(),
+                '1' => square.set_x(),
+                '2' => square.set_o(),
+                _ => return Err(GameError::InvalidEncoding),
+            }
         }
-        board
+        Ok(board)
     }
 
+    /// Encodes a board as a base-3 number.
+    ///
+    /// The top-left square is the most significant digit, so the number sorts in the same order
+    /// as the string produced by [`BoardCodec::encode`].
+    ///
+    /// # Arguments
+    ///
+    /// * `board` - The board to encode.
+    ///
+    /// # Returns
+    ///
+    /// A number in the range `0..19683`.
+    #[must_use]
     pub fn to_number(&self, board: &Board) -> u16 {
-        let mut number = 0;
-        for row in board.iter() {
-            for cell in row.iter() {
-                number = number * 3
-                    + match cell {
-                        Cell::Empty => 0,
-                        Cell::X => 1,
-                        Cell::O => 2,
-                    };
+        Coord::all().fold(0, |number, coord| {
+            number * 3
+                + match board.square(coord).get_value() {
+                    SquareValue::Empty => 0,
+                    SquareValue::X => 1,
+                    SquareValue::O => 2,
+                }
+        })
+    }
+
+    /// Decodes a board from a base-3 number.
+    ///
+    /// # Arguments
+    ///
+    /// * `number` - The encoded board, as produced by [`BoardCodec::to_number`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`GameError::InvalidEncoding`] if the number is 19683 (`3^9`) or greater.
+    pub fn from_number(&self, number: u16) -> Result<Board, GameError> {
+        if number >= BOARD_COUNT {
+            return Err(GameError::InvalidEncoding);
+        }
+
+        let mut board = Board::new();
+        let mut remaining = number;
+        for index in (0..9).rev() {
+            let square = board.square_mut(Coord::from_index(index)?);
+            match remaining % 3 {
+                1 => square.set_x(),
+                2 => square.set_o(),
+                _ => square.set_empty(),
             }
+            remaining /= 3;
         }
-        number
+        Ok(board)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::*;
+
+    #[test]
+    fn test_empty_board() {
+        let codec = BoardCodec::new();
+        let board = Board::new();
+        assert_eq!(codec.encode(&board), "000000000");
+        assert_eq!(codec.to_number(&board), 0);
+    }
+
+    #[test]
+    fn test_full_o_board() {
+        let codec = BoardCodec::new();
+        let board = codec.decode("222222222").unwrap();
+        assert_eq!(codec.to_number(&board), BOARD_COUNT - 1);
+        assert_eq!(codec.from_number(BOARD_COUNT - 1).unwrap(), board);
+    }
+
+    #[test]
+    fn test_every_number_round_trips() {
+        let codec = BoardCodec::new();
+        for number in 0..BOARD_COUNT {
+            let board = codec.from_number(number).unwrap();
+            assert_eq!(codec.to_number(&board), number);
+            assert_eq!(codec.decode(&codec.encode(&board)).unwrap(), board);
+        }
+    }
+
+    #[rstest]
+    #[case("")]
+    #[case("00000000")]
+    #[case("0000000000")]
+    #[case("00000000x")]
+    #[case("0000-0000")]
+    fn test_decode_rejects_invalid_input(#[case] encoded: &str) {
+        assert_eq!(
+            BoardCodec::new().decode(encoded),
+            Err(GameError::InvalidEncoding)
+        );
+    }
+
+    #[test]
+    fn test_from_number_rejects_out_of_range() {
+        let codec = BoardCodec::new();
+        assert_eq!(
+            codec.from_number(BOARD_COUNT),
+            Err(GameError::InvalidEncoding)
+        );
+        assert_eq!(codec.from_number(u16::MAX), Err(GameError::InvalidEncoding));
     }
 }
@@ -3,14 +3,17 @@
 // SPDX-License-Identifier: Apache-2.0
 // SPDX-License-Identifier: MIT
 
-use std::fmt::{
-    Display,
-    Error,
-    Formatter,
+use std::{
+    fmt::{
+        Display,
+        Error,
+        Formatter,
+    },
+    io,
 };
 
 #[allow(dead_code)]
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum GameError {
     SquareAlreadyX,
     SquareAlreadyO,
@@ -18,6 +21,10 @@ pub enum GameError {
     InvalidSquare,
     GameAlreadyWon,
     InvalidTriple,
+    BothPlayersWon,
+    InvalidEncoding,
+    UnreachablePosition,
+    InvalidSymbol,
 }
 
 impl Display for GameError {
@@ -29,6 +36,60 @@ impl Display for GameError {
             Self::SquareAlreadyO => write!(f, "Square already O"),
             Self::SquareNotEmpty => write!(f, "Square not empty"),
             Self::InvalidTriple => write!(f, "Invalid triple"),
+            Self::BothPlayersWon => write!(f, "Both players have a winning line"),
+            Self::InvalidEncoding => write!(f, "Invalid board encoding"),
+            Self::UnreachablePosition => write!(f, "Position cannot occur in a legal game"),
+            Self::InvalidSymbol => write!(f, "Invalid symbol"),
         }
     }
 }
+
+/// An error raised while saving or loading a trained MENACE.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PersistenceError {
+    /// The file could not be read or written.
+    Io(io::ErrorKind),
+    /// The document is not valid JSON, or does not have the expected fields.
+    Json(String),
+    /// The document or database was written in a format version this library cannot read.
+    UnsupportedVersion(u32),
+    /// A matchbox has a key that is not a board code, or beads for squares that are taken.
+    InvalidMatchbox(String),
+    /// The matchbox database reported an error.
+    Database(String),
+}
+
+impl Display for PersistenceError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), Error> {
+        match self {
+            Self::Io(kind) => write!(f, "I/O error: {kind}"),
+            Self::Json(message) => write!(f, "Invalid MENACE document: {message}"),
+            Self::UnsupportedVersion(version) => {
+                write!(f, "Unsupported MENACE format version {version}")
+            }
+            Self::InvalidMatchbox(key) => write!(f, "Invalid matchbox \"{key}\""),
+            Self::Database(message) => write!(f, "Database error: {message}"),
+        }
+    }
+}
+
+impl std::error::Error for PersistenceError {}
+
+impl From<io::Error> for PersistenceError {
+    fn from(error: io::Error) -> Self {
+        Self::Io(error.kind())
+    }
+}
+
+impl From<serde_json::Error> for PersistenceError {
+    fn from(error: serde_json::Error) -> Self {
+        Self::Json(error.to_string())
+    }
+}
+
+#[cfg(feature = "sqlite")]
+impl From<rusqlite::Error> for PersistenceError {
+    fn from(error: rusqlite::Error) -> Self {
+        Self::Database(error.to_string())
+    }
+}
@@ -5,16 +5,19 @@
 
 use crate::{
     Board,
+    Coord,
     GameError,
+    GameStatus,
     Player,
     Symbol,
 };
 
 /// Represents a complete game of Tic Tac Toe between two players.
 ///
-/// The `Game` struct manages two players, the game board, and the game state,
-/// including which player (if any) has won.
+/// The `Game` struct manages two players and the game board. Whether the game is over, and who
+/// won, is always read from the board through [`Game::status`].
 #[allow(dead_code)]
+#[derive(Debug, Clone)]
 pub struct Game {
     /// Player using the 'X' symbol.
     player_x: Player,
@@ -24,16 +27,13 @@ pub struct Game {
 
     /// The current state of the 3x3 game board.
     board: Board,
-
-    /// The winner of the game, if one exists.
-    winner: Option<Player>,
 }
 
 impl Game {
     /// Creates a new `Game` instance with two players and an empty board.
     ///
     /// `player_x` is always assigned the `Symbol::X`, and `player_o` is assigned `Symbol::O`.
-    /// The board is initialized to its default empty state, and no winner is set.
+    /// The board is initialized to its default empty state.
     ///
     /// # Returns
     ///
@@ -46,26 +46,121 @@ impl Game {
             player_x: current_player,
             player_o: other_player,
             board:    Board::new(),
-            winner:   None,
         }
     }
 
-    /// Plays a hardcoded move to the center of the board (`(2, 2)`).
+    /// Plays a move for the current player at the specified row and column.
+    ///
+    /// The move is validated against the current state of the game, the square is marked with
+    /// the current player's symbol, and the turn passes to the other player.
+    ///
+    /// # Returns
+    ///
+    /// The [`GameStatus`] of the game after the move has been played.
     ///
-    /// This is a stub method and should be expanded to allow interactive gameplay,
-    /// validation, turn management, and win detection.
+    /// # Arguments
+    ///
+    /// * `row` - Row number (1-based, valid values: 1 to 3).
+    /// * `col` - Column number (1-based, valid values: 1 to 3).
     ///
     /// # Errors
     ///
-    /// Returns a `GameError` if the move is invalid or the game is already over.
+    /// - [`GameError::GameAlreadyWon`] if the game is already over, whether won or drawn.
+    /// - [`GameError::InvalidSquare`] if the row or column are outside the range 1..=3.
+    /// - [`GameError::SquareAlreadyX`] or [`GameError::SquareAlreadyO`] if the square is taken.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tictacrustle::{
+    ///     Game,
+    ///     GameStatus,
+    ///     Symbol,
+    /// };
+    ///
+    /// let mut game = Game::new();
+    /// let status = game.play_move(2, 2).unwrap();
+    /// assert_eq!(status, GameStatus::InProgress { to_move: Symbol::O });
+    /// assert!(game.play_move(2, 2).is_err());
+    /// ```
+    pub fn play_move(&mut self, row: usize, col: usize) -> Result<GameStatus, GameError> {
+        self.play(Coord::new(row, col)?)
+    }
+
+    /// Plays a move for the current player at a validated coordinate.
+    ///
+    /// This behaves exactly like [`Game::play_move`], but takes a [`Coord`] so the square is
+    /// known to be on the board.
+    ///
+    /// # Arguments
+    ///
+    /// * `coord` - The square to mark with the current player's symbol.
     ///
     /// # Returns
     ///
-    /// A `Result` indicating whether the move was successful.
-    #[allow(dead_code)]
-    pub const fn play(&mut self) -> Result<(), GameError> {
-        self.board.get_square_mut(2, 2).set_x();
-        Ok(())
+    /// The [`GameStatus`] of the game after the move has been played.
+    ///
+    /// # Errors
+    ///
+    /// - [`GameError::GameAlreadyWon`] if the game is already over, whether won or drawn.
+    /// - [`GameError::SquareAlreadyX`] or [`GameError::SquareAlreadyO`] if the square is taken.
+    pub fn play(&mut self, coord: Coord) -> Result<GameStatus, GameError> {
+        if self.status().is_over() {
+            return Err(GameError::GameAlreadyWon);
+        }
+
+        let player = self.current_player().clone();
+        let square = self.board.square_mut(coord);
+        if square.is_x() {
+            return Err(GameError::SquareAlreadyX);
+        }
+        if square.is_o() {
+            return Err(GameError::SquareAlreadyO);
+        }
+        match player.symbol {
+            Symbol::X => square.set_x(),
+            Symbol::O => square.set_o(),
+        }
+        Ok(self.status())
+    }
+
+    /// Returns the current status of the game.
+    ///
+    /// # Returns
+    ///
+    /// A [`GameStatus`] describing whether the game is in progress, won, or drawn.
+    #[must_use]
+    pub fn status(&self) -> GameStatus {
+        self.board.status()
+    }
+
+    /// Returns the player whose turn it is.
+    ///
+    /// # Returns
+    ///
+    /// A reference to the `Player` that will make the next move.
+    #[must_use]
+    pub fn current_player(&self) -> &Player {
+        match self.board.to_move() {
+            Symbol::X => &self.player_x,
+            Symbol::O => &self.player_o,
+        }
+    }
+
+    /// Checks whether the game has ended in a draw.
+    ///
+    /// # Returns
+    ///
+    /// `true` if every square is filled and no player has won, `false` otherwise.
+    #[must_use]
+    pub fn is_draw(&self) -> bool {
+        matches!(self.status(), GameStatus::Draw)
+    }
+
+    /// Checks whether the game has ended, either with a winner or in a draw.
+    #[must_use]
+    pub fn is_over(&self) -> bool {
+        self.status().is_over()
     }
 
     /// Returns the winner of the game, if any.
@@ -73,11 +168,12 @@ impl Game {
     /// # Returns
     ///
     /// An `Option<Player>` which is `Some(player)` if a winner has been determined,
-    /// or `None` if the game is still ongoing or ended in a draw.
+    /// or `None` if the game is still ongoing or ended in a draw. Use [`Game::status`] to tell
+    /// those two cases apart.
     #[allow(dead_code)]
     #[must_use]
     pub fn winner(&self) -> Option<Player> {
-        self.winner.clone()
+        self.status().winner().map(|symbol| Player { symbol })
     }
 
     /// Returns an immutable reference to the current state of the game board.
@@ -104,9 +200,16 @@ impl Default for Game {
 #[allow(clippy::unwrap_used)]
 mod test {
 
+    use rstest::rstest;
+
     use crate::{
+        Coord,
         Game,
+        GameError,
+        GameStatus,
+        Line,
         SquareValue,
+        Symbol,
     };
 
     #[test]
@@ -137,10 +240,10 @@ mod test {
     }
 
     #[test]
-    fn play_sets_center_square() {
+    fn play_move_sets_center_square() {
         let mut game = Game::new();
 
-        game.play().unwrap();
+        game.play_move(2, 2).unwrap();
 
         let center = game.board().get_square(2, 2).get_value();
         assert_eq!(center, SquareValue::X);
@@ -153,9 +256,99 @@ mod test {
     }
 
     #[test]
-    fn play_returns_ok() {
+    fn play_move_returns_ok() {
         let mut game = Game::new();
-        let result = game.play();
+        let result = game.play_move(1, 1);
         assert!(result.is_ok());
     }
+
+    #[test]
+    fn play_marks_coord() {
+        let mut game = Game::new();
+        let coord = Coord::from_keypad(1).unwrap();
+
+        game.play(coord).unwrap();
+
+        assert!(game.board().get_square(3, 1).is_x());
+        assert_eq!(game.play(coord), Err(GameError::SquareAlreadyX));
+    }
+
+    #[test]
+    fn play_move_alternates_turns() {
+        let mut game = Game::new();
+        assert_eq!(game.current_player().symbol, Symbol::X);
+
+        game.play_move(1, 1).unwrap();
+        assert_eq!(game.current_player().symbol, Symbol::O);
+
+        game.play_move(2, 2).unwrap();
+        assert_eq!(game.current_player().symbol, Symbol::X);
+        assert_eq!(game.board().get_square(2, 2).get_value(), SquareValue::O);
+    }
+
+    #[rstest]
+    #[case(0, 1)]
+    #[case(1, 0)]
+    #[case(4, 2)]
+    #[case(2, 4)]
+    fn play_move_rejects_out_of_range(#[case] row: usize, #[case] col: usize) {
+        let mut game = Game::new();
+        assert_eq!(game.play_move(row, col), Err(GameError::InvalidSquare));
+    }
+
+    #[test]
+    fn play_move_rejects_occupied_squares() {
+        let mut game = Game::new();
+        game.play_move(1, 1).unwrap();
+        game.play_move(2, 2).unwrap();
+
+        assert_eq!(game.play_move(1, 1), Err(GameError::SquareAlreadyX));
+        assert_eq!(game.play_move(2, 2), Err(GameError::SquareAlreadyO));
+        assert_eq!(game.current_player().symbol, Symbol::X);
+    }
+
+    #[test]
+    fn play_move_detects_winner() {
+        let mut game = Game::new();
+        for (row, col) in [(1, 1), (2, 1), (1, 2), (2, 2), (1, 3)] {
+            game.play_move(row, col).unwrap();
+        }
+
+        assert_eq!(game.winner().map(|player| player.symbol), Some(Symbol::X));
+        assert_eq!(
+            game.status(),
+            GameStatus::Won {
+                symbol: Symbol::X,
+                line:   Line::row(1).unwrap(),
+            }
+        );
+        assert!(game.is_over());
+        assert!(!game.is_draw());
+        assert_eq!(game.play_move(3, 3), Err(GameError::GameAlreadyWon));
+    }
+
+    #[test]
+    fn play_move_detects_draw() {
+        let mut game = Game::new();
+        let mut status = game.status();
+        for (row, col) in [
+            (1, 1),
+            (2, 2),
+            (1, 2),
+            (1, 3),
+            (3, 1),
+            (2, 1),
+            (2, 3),
+            (3, 2),
+            (3, 3),
+        ] {
+            status = game.play_move(row, col).unwrap();
+        }
+
+        assert_eq!(status, GameStatus::Draw);
+        assert!(game.winner().is_none());
+        assert!(game.is_draw());
+        assert!(game.is_over());
+        assert_eq!(game.play_move(1, 1), Err(GameError::GameAlreadyWon));
+    }
 }
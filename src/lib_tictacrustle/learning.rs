@@ -0,0 +1,187 @@
+// SPDX-FileCopyrightText: 2022 - 2024 Ali Sajid Imami
+//
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+mod q_learning;
+mod td;
+
+pub use self::{
+    q_learning::QLearningAgent,
+    td::TdAgent,
+};
+use crate::GameResult;
+
+/// Clamps a rate, probability or discount to `0.0..=1.0`, treating NaN as zero.
+///
+/// # Arguments
+///
+/// * `value` - The value to clamp.
+pub(crate) fn unit_interval(value: f64) -> f64 {
+    if value.is_nan() {
+        0.0
+    } else {
+        value.clamp(0.0, 1.0)
+    }
+}
+
+/// A hyperparameter that changes as an agent plays more games.
+///
+/// # Examples
+///
+/// ```
+/// use tictacrustle::Schedule;
+///
+/// let epsilon = Schedule::Linear {
+///     start: 0.5,
+///     end:   0.1,
+///     games: 100,
+/// };
+/// assert!((epsilon.value(0) - 0.5).abs() < 1e-9);
+/// assert!((epsilon.value(50) - 0.3).abs() < 1e-9);
+/// assert!((epsilon.value(1_000) - 0.1).abs() < 1e-9);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Schedule {
+    /// The same value for every game.
+    Constant(f64),
+    /// Moves in a straight line from `start` to `end` over the given number of games, then
+    /// stays at `end`.
+    Linear {
+        /// The value for the first game.
+        start: f64,
+        /// The value once `games` games have been played.
+        end:   f64,
+        /// The number of games over which the value changes.
+        games: u64,
+    },
+    /// Starts at `start` and is multiplied by `decay` after every game, but never falls below
+    /// `min`.
+    Exponential {
+        /// The value for the first game.
+        start: f64,
+        /// The factor applied after every game, between 0 and 1.
+        decay: f64,
+        /// The smallest value the schedule reaches.
+        min:   f64,
+    },
+}
+
+impl Schedule {
+    /// Returns the value of the hyperparameter for the next game.
+    ///
+    /// # Arguments
+    ///
+    /// * `games_played` - The number of games the agent has finished so far.
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn value(&self, games_played: u64) -> f64 {
+        match *self {
+            Self::Constant(value) => value,
+            Self::Linear { start, end, games } => {
+                if games_played >= games {
+                    end
+                } else {
+                    start + (end - start) * (games_played as f64 / games as f64)
+                }
+            }
+            Self::Exponential { start, decay, min } => {
+                let exponent = i32::try_from(games_played).unwrap_or(i32::MAX);
+                (start * decay.powi(exponent)).max(min)
+            }
+        }
+    }
+
+    /// Returns the value of the hyperparameter for the next game as a probability or step size,
+    /// clamped to `0.0..=1.0` with NaN treated as zero.
+    ///
+    /// # Arguments
+    ///
+    /// * `games_played` - The number of games the agent has finished so far.
+    #[must_use]
+    pub fn probability(&self, games_played: u64) -> f64 {
+        unit_interval(self.value(games_played))
+    }
+}
+
+/// The settings shared by the tabular learning agents.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LearningConfig {
+    /// The learning rate: how far each estimate moves towards its target. Read through
+    /// [`Schedule::probability`].
+    pub alpha:   Schedule,
+    /// The discount applied to the value of later positions, clamped like
+    /// [`Schedule::probability`].
+    pub gamma:   f64,
+    /// The exploration rate: the probability of playing a random move instead of the best one.
+    /// Read through [`Schedule::probability`].
+    pub epsilon: Schedule,
+}
+
+impl Default for LearningConfig {
+    /// Creates a configuration with a learning rate of 0.1, a discount of 0.9 and an exploration
+    /// rate of 0.1.
+    fn default() -> Self {
+        Self {
+            alpha:   Schedule::Constant(0.1),
+            gamma:   0.9,
+            epsilon: Schedule::Constant(0.1),
+        }
+    }
+}
+
+/// Returns the reward for finishing a game with the given result: 1 for a win, 0 for a draw,
+/// and -1 for a loss.
+#[must_use]
+pub const fn terminal_reward(result: GameResult) -> f64 {
+    match result {
+        GameResult::Win => 1.0,
+        GameResult::Draw => 0.0,
+        GameResult::Loss => -1.0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::*;
+
+    /// The preferences of an opponent that takes the edges before the corners, shared by the
+    /// tests of both learning agents.
+    pub(super) fn edges_first() -> Vec<crate::Coord> {
+        [2, 4, 6, 8, 1, 3, 7, 9, 5]
+            .into_iter()
+            .map(|key| crate::Coord::from_keypad(key).unwrap())
+            .collect()
+    }
+
+    #[rstest]
+    #[case(Schedule::Constant(0.2), 0, 0.2)]
+    #[case(Schedule::Constant(0.2), 500, 0.2)]
+    #[case(Schedule::Linear { start: 1.0, end: 0.0, games: 10 }, 5, 0.5)]
+    #[case(Schedule::Linear { start: 1.0, end: 0.0, games: 10 }, 20, 0.0)]
+    #[case(Schedule::Exponential { start: 1.0, decay: 0.5, min: 0.1 }, 2, 0.25)]
+    #[case(Schedule::Exponential { start: 1.0, decay: 0.5, min: 0.1 }, 10, 0.1)]
+    fn test_schedule(#[case] schedule: Schedule, #[case] games: u64, #[case] expected: f64) {
+        assert!((schedule.value(games) - expected).abs() < 1e-9);
+    }
+
+    #[rstest]
+    #[case(Schedule::Constant(0.2), 0.2)]
+    #[case(Schedule::Constant(-0.5), 0.0)]
+    #[case(Schedule::Constant(1.5), 1.0)]
+    #[case(Schedule::Constant(f64::NAN), 0.0)]
+    #[case(Schedule::Linear { start: f64::NAN, end: 0.5, games: 10 }, 0.0)]
+    fn test_schedule_probability(#[case] schedule: Schedule, #[case] expected: f64) {
+        assert!((schedule.probability(0) - expected).abs() < 1e-9);
+    }
+
+    #[rstest]
+    #[case(GameResult::Win, 1.0)]
+    #[case(GameResult::Draw, 0.0)]
+    #[case(GameResult::Loss, -1.0)]
+    fn test_terminal_reward(#[case] result: GameResult, #[case] expected: f64) {
+        assert!((terminal_reward(result) - expected).abs() < f64::EPSILON);
+    }
+}
@@ -0,0 +1,282 @@
+// SPDX-FileCopyrightText: 2022 - 2024 Ali Sajid Imami
+//
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use std::collections::HashMap;
+
+use rand::{
+    seq::IndexedRandom,
+    Rng,
+    SeedableRng,
+};
+
+use super::{
+    terminal_reward,
+    unit_interval,
+    LearningConfig,
+};
+use crate::{
+    Agent,
+    Board,
+    BoardCodec,
+    Coord,
+    MatchOutcome,
+    Reportable,
+    Seeded,
+    SeededRng,
+    Symbol,
+};
+
+/// An agent that learns the value of each move in each position with tabular Q-learning.
+///
+/// Like MENACE-C, the table has one entry per [`Board::canonical`] position, so symmetric
+/// positions share what they learn. Moves are chosen epsilon-greedily. Once a game is over, the
+/// moves played are updated in a single backward pass: the last move moves towards the final
+/// reward, and every earlier move moves towards the discounted best value of the position the
+/// agent faced next.
+///
+/// # Examples
+///
+/// ```
+/// use tictacrustle::{
+///     play_match,
+///     LearningConfig,
+///     QLearningAgent,
+///     RandomAgent,
+/// };
+///
+/// let mut learner = QLearningAgent::new(LearningConfig::default());
+/// let mut opponent = RandomAgent::new();
+///
+/// for _ in 0..10 {
+///     play_match(&mut learner, &mut opponent).unwrap();
+/// }
+/// assert_eq!(learner.games_played(), 10);
+/// assert!(!learner.is_empty());
+/// ```
+#[derive(Debug, Clone)]
+pub struct QLearningAgent {
+    /// The hyperparameters of the agent.
+    config:       LearningConfig,
+    /// The estimated value of each move, keyed by the base-3 number of the canonical board and
+    /// indexed by the move's square on the canonical board.
+    table:        HashMap<u16, [f64; 9]>,
+    /// The canonical positions faced and canonical moves played in the current game.
+    history:      Vec<(Board, Coord)>,
+    /// The number of games the agent has learned from.
+    games_played: u64,
+    /// The random number generator used for exploration and tie-breaking.
+    rng:          SeededRng,
+}
+
+impl QLearningAgent {
+    /// Creates a new `QLearningAgent` with an empty table, seeded from the operating system.
+    ///
+    /// Use [`Seeded::with_seed`] to make its play reproducible.
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - The hyperparameters of the agent.
+    #[must_use]
+    pub fn new(config: LearningConfig) -> Self {
+        Self {
+            config,
+            table: HashMap::new(),
+            history: Vec::new(),
+            games_played: 0,
+            rng: SeededRng::from_os_rng(),
+        }
+    }
+
+    /// Returns the hyperparameters of the agent.
+    #[must_use]
+    pub const fn config(&self) -> &LearningConfig {
+        &self.config
+    }
+
+    /// Returns the estimated value of playing a move, which is zero for moves never tried.
+    ///
+    /// # Arguments
+    ///
+    /// * `board` - The position the move is played in.
+    /// * `coord` - The move.
+    #[must_use]
+    pub fn q_value(&self, board: &Board, coord: Coord) -> f64 {
+        let (canonical, transform) = board.canonical();
+        self.value_of(&canonical, coord.transform(transform))
+    }
+
+    /// Returns the number of games the agent has learned from.
+    #[must_use]
+    pub const fn games_played(&self) -> u64 {
+        self.games_played
+    }
+
+    /// Returns the number of positions in the table.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.table.len()
+    }
+
+    /// Checks whether the table is empty.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.table.is_empty()
+    }
+
+    /// Returns the estimated value of a move on a canonical board.
+    fn value_of(&self, canonical: &Board, coord: Coord) -> f64 {
+        self.table
+            .get(&BoardCodec::new().to_number(canonical))
+            .map_or(0.0, |values| values[coord.index()])
+    }
+
+    /// Returns the best estimated value of any legal move on a canonical board.
+    fn best_value(&self, canonical: &Board) -> f64 {
+        canonical
+            .legal_moves()
+            .into_iter()
+            .map(|coord| self.value_of(canonical, coord))
+            .reduce(f64::max)
+            .unwrap_or(0.0)
+    }
+}
+
+impl Seeded for QLearningAgent {
+    fn rng_mut(&mut self) -> &mut SeededRng {
+        &mut self.rng
+    }
+}
+
+impl Reportable for QLearningAgent {}
+
+impl Agent for QLearningAgent {
+    fn choose_move(&mut self, board: &Board, _symbol: Symbol) -> Option<Coord> {
+        let (canonical, transform) = board.canonical();
+        let legal = canonical.legal_moves();
+        let epsilon = self.config.epsilon.probability(self.games_played);
+
+        let choice = if self.rng.random_bool(epsilon) {
+            legal.choose(&mut self.rng).copied()
+        } else {
+            let best = self.best_value(&canonical);
+            let greedy: Vec<Coord> = legal
+                .into_iter()
+                .filter(|&coord| self.value_of(&canonical, coord) >= best)
+                .collect();
+            greedy.choose(&mut self.rng).copied()
+        }?;
+
+        self.history.push((canonical, choice));
+        Some(choice.transform(transform.inverse()))
+    }
+
+    fn on_game_end(&mut self, outcome: &MatchOutcome, symbol: Symbol) {
+        let alpha = self.config.alpha.probability(self.games_played);
+        let gamma = unit_interval(self.config.gamma);
+        let mut target = terminal_reward(outcome.result_for(symbol));
+
+        for (canonical, coord) in std::mem::take(&mut self.history).into_iter().rev() {
+            let key = BoardCodec::new().to_number(&canonical);
+            let values = self.table.entry(key).or_insert([0.0; 9]);
+            let value = &mut values[coord.index()];
+            *value += alpha * (target - *value);
+            target = gamma * self.best_value(&canonical);
+        }
+
+        self.games_played += 1;
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use rstest::rstest;
+
+    use super::*;
+    use crate::{
+        learning::tests::edges_first,
+        play_match,
+        GameResult,
+        Schedule,
+        ScriptedAgent,
+        Transform,
+    };
+
+    fn greedy() -> LearningConfig {
+        LearningConfig {
+            alpha:   Schedule::Constant(0.5),
+            gamma:   0.9,
+            epsilon: Schedule::Constant(0.0),
+        }
+    }
+
+    #[test]
+    fn test_update_after_win() {
+        let mut agent = QLearningAgent::new(greedy()).with_seed(1);
+        let board = BoardCodec::new().decode("110220000").unwrap();
+        let coord = agent.choose_move(&board, Symbol::X).unwrap();
+
+        let outcome = MatchOutcome::Resigned(Symbol::O);
+        agent.on_game_end(&outcome, Symbol::X);
+
+        assert!((agent.q_value(&board, coord) - 0.5).abs() < 1e-9);
+        assert_eq!(agent.games_played(), 1);
+        assert_eq!(agent.len(), 1);
+    }
+
+    #[rstest]
+    #[case(f64::NAN, 0.0)]
+    #[case(-0.5, 0.0)]
+    #[case(2.0, 1.0)]
+    fn test_learning_rate_is_clamped(#[case] alpha: f64, #[case] expected: f64) {
+        let mut agent = QLearningAgent::new(LearningConfig {
+            alpha: Schedule::Constant(alpha),
+            ..greedy()
+        })
+        .with_seed(1);
+        let board = BoardCodec::new().decode("110220000").unwrap();
+        let coord = agent.choose_move(&board, Symbol::X).unwrap();
+        agent.on_game_end(&MatchOutcome::Resigned(Symbol::O), Symbol::X);
+
+        assert!((agent.q_value(&board, coord) - expected).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_symmetric_positions_share_values() {
+        let mut agent = QLearningAgent::new(greedy()).with_seed(1);
+        // No rotation or reflection maps this board onto itself, so each image is distinct.
+        let board = BoardCodec::new().decode("120000000").unwrap();
+        let coord = agent.choose_move(&board, Symbol::X).unwrap();
+        agent.on_game_end(&MatchOutcome::Resigned(Symbol::O), Symbol::X);
+
+        for transform in Transform::ALL {
+            let value = agent.q_value(&board.transform(transform), coord.transform(transform));
+            assert!((value - 0.5).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_learns_to_beat_a_weak_opponent() {
+        let config = LearningConfig {
+            alpha:   Schedule::Constant(0.3),
+            gamma:   0.9,
+            epsilon: Schedule::Linear {
+                start: 0.3,
+                end:   0.0,
+                games: 2_000,
+            },
+        };
+        let mut agent = QLearningAgent::new(config).with_seed(1);
+        let mut opponent = ScriptedAgent::new(edges_first());
+
+        for _ in 0..2_000 {
+            play_match(&mut agent, &mut opponent).unwrap();
+        }
+        for _ in 0..10 {
+            let record = play_match(&mut agent, &mut opponent).unwrap();
+            assert_eq!(record.outcome.result_for(Symbol::X), GameResult::Win);
+        }
+    }
+}
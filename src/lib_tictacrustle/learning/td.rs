@@ -0,0 +1,295 @@
+// SPDX-FileCopyrightText: 2022 - 2024 Ali Sajid Imami
+//
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use std::collections::HashMap;
+
+use rand::{
+    seq::IndexedRandom,
+    Rng,
+    SeedableRng,
+};
+
+use super::{
+    terminal_reward,
+    unit_interval,
+    LearningConfig,
+};
+use crate::{
+    Agent,
+    Board,
+    BoardCodec,
+    Coord,
+    GameResult,
+    GameStatus,
+    MatchOutcome,
+    Reportable,
+    Seeded,
+    SeededRng,
+    Symbol,
+};
+
+/// An agent that learns the value of afterstates with TD(0).
+///
+/// An afterstate is the position just after the agent has moved. The agent keeps one value per
+/// [`Board::canonical`] afterstate and, epsilon-greedily, plays the move leading to the best one.
+/// Afterstates that end the game are valued by their result rather than by the table. Once a
+/// game is over, the afterstates reached are updated in a single backward pass: the last one
+/// moves towards the final reward, and every earlier one moves towards the discounted value of
+/// the afterstate that followed it.
+///
+/// # Examples
+///
+/// ```
+/// use tictacrustle::{
+///     play_match,
+///     LearningConfig,
+///     RandomAgent,
+///     TdAgent,
+/// };
+///
+/// let mut learner = TdAgent::new(LearningConfig::default());
+/// let mut opponent = RandomAgent::new();
+///
+/// for _ in 0..10 {
+///     play_match(&mut opponent, &mut learner).unwrap();
+/// }
+/// assert_eq!(learner.games_played(), 10);
+/// assert!(!learner.is_empty());
+/// ```
+#[derive(Debug, Clone)]
+pub struct TdAgent {
+    /// The hyperparameters of the agent.
+    config:       LearningConfig,
+    /// The estimated value of each afterstate, keyed by the base-3 number of its canonical
+    /// board.
+    values:       HashMap<u16, f64>,
+    /// The afterstates reached in the current game.
+    history:      Vec<Board>,
+    /// The number of games the agent has learned from.
+    games_played: u64,
+    /// The random number generator used for exploration and tie-breaking.
+    rng:          SeededRng,
+}
+
+impl TdAgent {
+    /// Creates a new `TdAgent` with an empty table, seeded from the operating system.
+    ///
+    /// Use [`Seeded::with_seed`] to make its play reproducible.
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - The hyperparameters of the agent.
+    #[must_use]
+    pub fn new(config: LearningConfig) -> Self {
+        Self {
+            config,
+            values: HashMap::new(),
+            history: Vec::new(),
+            games_played: 0,
+            rng: SeededRng::from_os_rng(),
+        }
+    }
+
+    /// Returns the hyperparameters of the agent.
+    #[must_use]
+    pub const fn config(&self) -> &LearningConfig {
+        &self.config
+    }
+
+    /// Returns the estimated value of an afterstate for the player who just moved.
+    ///
+    /// Finished games are valued by their result, and afterstates never reached are valued at
+    /// zero.
+    ///
+    /// # Arguments
+    ///
+    /// * `afterstate` - The position just after the player moved.
+    #[must_use]
+    pub fn value(&self, afterstate: &Board) -> f64 {
+        match afterstate.status() {
+            GameStatus::Won { .. } => terminal_reward(GameResult::Win),
+            GameStatus::Draw => terminal_reward(GameResult::Draw),
+            GameStatus::InProgress { .. } => self
+                .values
+                .get(&Self::key(afterstate))
+                .copied()
+                .unwrap_or(0.0),
+        }
+    }
+
+    /// Returns the number of games the agent has learned from.
+    #[must_use]
+    pub const fn games_played(&self) -> u64 {
+        self.games_played
+    }
+
+    /// Returns the number of afterstates in the table.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Checks whether the table is empty.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Returns the table key of a board.
+    fn key(board: &Board) -> u16 {
+        BoardCodec::new().to_number(&board.canonical().0)
+    }
+}
+
+impl Seeded for TdAgent {
+    fn rng_mut(&mut self) -> &mut SeededRng {
+        &mut self.rng
+    }
+}
+
+impl Reportable for TdAgent {}
+
+impl Agent for TdAgent {
+    fn choose_move(&mut self, board: &Board, _symbol: Symbol) -> Option<Coord> {
+        let afterstates: Vec<(Coord, Board)> = board
+            .legal_moves()
+            .into_iter()
+            .filter_map(|coord| board.after_move(coord).ok().map(|next| (coord, next)))
+            .collect();
+        let epsilon = self.config.epsilon.probability(self.games_played);
+
+        let (coord, afterstate) = if self.rng.random_bool(epsilon) {
+            afterstates.choose(&mut self.rng).copied()
+        } else {
+            let best = afterstates
+                .iter()
+                .map(|(_, next)| self.value(next))
+                .reduce(f64::max)?;
+            let greedy: Vec<(Coord, Board)> = afterstates
+                .into_iter()
+                .filter(|(_, next)| self.value(next) >= best)
+                .collect();
+            greedy.choose(&mut self.rng).copied()
+        }?;
+
+        self.history.push(afterstate);
+        Some(coord)
+    }
+
+    fn on_game_end(&mut self, outcome: &MatchOutcome, symbol: Symbol) {
+        let alpha = self.config.alpha.probability(self.games_played);
+        let gamma = unit_interval(self.config.gamma);
+        let mut target = terminal_reward(outcome.result_for(symbol));
+
+        for afterstate in std::mem::take(&mut self.history).into_iter().rev() {
+            // Afterstates that end the game are valued by their result and never learned.
+            let value = if afterstate.status().is_over() {
+                self.value(&afterstate)
+            } else {
+                let value = self.values.entry(Self::key(&afterstate)).or_insert(0.0);
+                *value += alpha * (target - *value);
+                *value
+            };
+            target = gamma * value;
+        }
+
+        self.games_played += 1;
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use rstest::rstest;
+
+    use super::*;
+    use crate::{
+        learning::tests::edges_first,
+        play_match,
+        Schedule,
+        ScriptedAgent,
+    };
+
+    fn greedy() -> LearningConfig {
+        LearningConfig {
+            alpha:   Schedule::Constant(0.5),
+            gamma:   0.5,
+            epsilon: Schedule::Constant(0.0),
+        }
+    }
+
+    #[test]
+    fn test_prefers_immediate_win() {
+        let mut agent = TdAgent::new(greedy()).with_seed(1);
+        let board = BoardCodec::new().decode("110220000").unwrap();
+        assert_eq!(agent.choose_move(&board, Symbol::X), Coord::new(1, 3).ok());
+    }
+
+    #[test]
+    fn test_backward_pass() {
+        let mut agent = TdAgent::new(greedy()).with_seed(1);
+        let first = Board::new();
+        agent.choose_move(&first, Symbol::X).unwrap();
+        let afterstate = agent.history[0];
+
+        agent.on_game_end(&MatchOutcome::Resigned(Symbol::X), Symbol::X);
+        assert!((agent.value(&afterstate) + 0.5).abs() < 1e-9);
+        assert_eq!(agent.games_played(), 1);
+        assert!(agent.history.is_empty());
+    }
+
+    #[test]
+    fn test_winning_move_backs_up_its_result() {
+        let mut agent = TdAgent::new(greedy()).with_seed(1);
+        let board = BoardCodec::new().decode("110020000").unwrap();
+        agent.history.push(board);
+        let winning = BoardCodec::new().decode("111220000").unwrap();
+        agent.history.push(winning);
+
+        let outcome = MatchOutcome::Finished(winning.status());
+        agent.on_game_end(&outcome, Symbol::X);
+        // The target of the first afterstate is gamma times the value of the win.
+        assert!((agent.value(&board) - 0.25).abs() < 1e-9);
+        assert_eq!(agent.len(), 1);
+    }
+
+    #[rstest]
+    #[case(f64::NAN, 0.0)]
+    #[case(-1.0, 0.0)]
+    #[case(2.0, 0.5)]
+    fn test_discount_is_clamped(#[case] gamma: f64, #[case] expected: f64) {
+        let mut agent = TdAgent::new(LearningConfig { gamma, ..greedy() }).with_seed(1);
+        let board = BoardCodec::new().decode("110020000").unwrap();
+        agent.history.push(board);
+        let winning = BoardCodec::new().decode("111220000").unwrap();
+        agent.history.push(winning);
+
+        agent.on_game_end(&MatchOutcome::Finished(winning.status()), Symbol::X);
+        assert!((agent.value(&board) - expected).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_learns_to_beat_a_weak_opponent() {
+        let config = LearningConfig {
+            alpha:   Schedule::Constant(0.3),
+            gamma:   0.9,
+            epsilon: Schedule::Linear {
+                start: 0.3,
+                end:   0.0,
+                games: 2_000,
+            },
+        };
+        let mut agent = TdAgent::new(config).with_seed(1);
+        let mut opponent = ScriptedAgent::new(edges_first());
+
+        for _ in 0..2_000 {
+            play_match(&mut agent, &mut opponent).unwrap();
+        }
+        for _ in 0..10 {
+            let record = play_match(&mut agent, &mut opponent).unwrap();
+            assert_eq!(record.outcome.winner(), Some(Symbol::X));
+        }
+    }
+}
@@ -6,19 +6,150 @@
 /// The `TicTacRustle` library
 ///
 /// This library provides the necessary components to create a Tic-Tac-Toe game.
+mod agent;
 mod board;
+mod coord;
+mod encoding;
 mod errors;
 mod game;
+mod learning;
+mod line;
+mod mcts;
+mod menace;
+mod opponents;
 mod player;
+mod protocol;
+mod search;
+mod seeded;
+mod solver;
 mod square;
 mod square_value;
+mod state_space;
+mod status;
+mod store;
+mod symmetry;
+mod trainer;
 
+pub use agent::{
+    play_match,
+    Agent,
+    MatchOutcome,
+    MatchRecord,
+    RandomAgent,
+    ScriptedAgent,
+};
 pub use board::Board;
-pub use errors::GameError;
+pub use coord::Coord;
+pub use encoding::BoardCodec;
+pub use errors::{
+    GameError,
+    PersistenceError,
+};
 pub use game::Game;
+pub use learning::{
+    terminal_reward,
+    LearningConfig,
+    QLearningAgent,
+    Schedule,
+    TdAgent,
+};
+pub use line::{
+    Line,
+    LineIndex,
+    WinningLine,
+};
+pub use mcts::{
+    MctsAgent,
+    MctsConfig,
+    MctsResult,
+    MoveStats,
+    RolloutPolicy,
+};
+pub use menace::{
+    initial_beads,
+    BeadDraw,
+    EmptyBoxPolicy,
+    Flavor,
+    Matchbox,
+    Menace,
+    MenaceAgent,
+    MenaceConfig,
+    ReinforcementPolicy,
+    RewardSchedule,
+    FORMAT_VERSION,
+};
+pub use opponents::{
+    Difficulty,
+    HeuristicAgent,
+    NoisyPerfectAgent,
+    PerfectAgent,
+};
 pub use player::{
     Player,
     Symbol,
 };
+pub use protocol::{
+    ErrorResponse,
+    GameEvent,
+    GameState,
+    GameView,
+    JoinRequest,
+    LobbyEntry,
+    MatchMove,
+    MatchView,
+    MoveRecord,
+    MoveResponse,
+    NewGameRequest,
+    OutcomeView,
+    PlayerTicket,
+    SessionSummary,
+};
+pub use search::{
+    Evaluator,
+    LineEvaluator,
+    Search,
+    SearchAgent,
+    SearchConfig,
+    SearchResult,
+    WIN_SCORE,
+};
+pub use seeded::{
+    Seeded,
+    SeededRng,
+};
+pub use solver::{
+    Evaluation,
+    Solver,
+};
 pub use square::Square;
 pub use square_value::SquareValue;
+pub use state_space::{
+    OutcomeCounts,
+    Reduction,
+    StateSpace,
+};
+pub use status::{
+    GameResult,
+    GameStatus,
+};
+pub use store::{
+    MatchboxStore,
+    MemoryStore,
+};
+#[cfg(feature = "sqlite")]
+pub use store::{
+    SqliteStore,
+    SCHEMA_VERSION,
+};
+pub use symmetry::Transform;
+pub use trainer::{
+    FirstPlayer,
+    LearnerStats,
+    Reportable,
+    ResultCounts,
+    StopCondition,
+    Trainer,
+    TrainerConfig,
+    TrainingReport,
+    TrainingSummary,
+};
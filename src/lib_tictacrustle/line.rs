@@ -0,0 +1,218 @@
+// SPDX-FileCopyrightText: 2022 - 2024 Ali Sajid Imami
+//
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use std::fmt;
+
+use crate::{
+    Coord,
+    GameError,
+    SquareValue,
+};
+
+/// Represents one of the eight lines on a Tic Tac Toe board that can win a game.
+///
+/// Rows and columns are numbered with 1-based indices (1 to 3), matching the coordinates used by
+/// [`Board`](crate::Board). The numbers are held in a [`LineIndex`], so a `Line` always lies on
+/// the board.
+///
+/// # Examples
+///
+/// ```
+/// use tictacrustle::Line;
+///
+/// assert_eq!(Line::ALL.len(), 8);
+/// assert_eq!(Line::row(1).unwrap().to_string(), "row 1");
+/// assert!(Line::row(4).is_err());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Line {
+    /// A horizontal line, identified by its row number.
+    Row(LineIndex),
+    /// A vertical line, identified by its column number.
+    Column(LineIndex),
+    /// The left-to-right diagonal (`(1,1) -> (3,3)`).
+    MainDiagonal,
+    /// The right-to-left diagonal (`(1,3) -> (3,1)`).
+    AntiDiagonal,
+}
+
+impl Line {
+    /// All eight lines of the board, rows first, then columns, then the two diagonals.
+    pub const ALL: [Self; 8] = [
+        Self::Row(LineIndex(1)),
+        Self::Row(LineIndex(2)),
+        Self::Row(LineIndex(3)),
+        Self::Column(LineIndex(1)),
+        Self::Column(LineIndex(2)),
+        Self::Column(LineIndex(3)),
+        Self::MainDiagonal,
+        Self::AntiDiagonal,
+    ];
+
+    /// Creates a validated row line.
+    ///
+    /// # Arguments
+    ///
+    /// * `row` - Row number (1-based, valid values: 1 to 3).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`GameError::InvalidSquare`] if the row is outside the range 1..=3.
+    pub const fn row(row: usize) -> Result<Self, GameError> {
+        match LineIndex::new(row) {
+            Ok(index) => Ok(Self::Row(index)),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Creates a validated column line.
+    ///
+    /// # Arguments
+    ///
+    /// * `col` - Column number (1-based, valid values: 1 to 3).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`GameError::InvalidSquare`] if the column is outside the range 1..=3.
+    pub const fn column(col: usize) -> Result<Self, GameError> {
+        match LineIndex::new(col) {
+            Ok(index) => Ok(Self::Column(index)),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Returns the coordinates of the three squares on the line.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tictacrustle::{
+    ///     Coord,
+    ///     Line,
+    /// };
+    ///
+    /// let coords = Line::AntiDiagonal.coords();
+    /// assert_eq!(coords[0], Coord::new(1, 3).unwrap());
+    /// ```
+    #[must_use]
+    pub fn coords(self) -> [Coord; 3] {
+        const ONE: LineIndex = LineIndex(1);
+        const TWO: LineIndex = LineIndex(2);
+        const THREE: LineIndex = LineIndex(3);
+
+        let pairs = match self {
+            Self::Row(row) => [(row, ONE), (row, TWO), (row, THREE)],
+            Self::Column(col) => [(ONE, col), (TWO, col), (THREE, col)],
+            Self::MainDiagonal => [(ONE, ONE), (TWO, TWO), (THREE, THREE)],
+            Self::AntiDiagonal => [(ONE, THREE), (TWO, TWO), (THREE, ONE)],
+        };
+        pairs.map(|(row, col)| Coord::from_line_indices(row, col))
+    }
+}
+
+/// The number of a row or column, validated to lie in the range 1..=3.
+///
+/// # Examples
+///
+/// ```
+/// use tictacrustle::LineIndex;
+///
+/// assert_eq!(LineIndex::new(2).unwrap().get(), 2);
+/// assert!(LineIndex::new(0).is_err());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct LineIndex(usize);
+
+impl LineIndex {
+    /// Creates a validated row or column number.
+    ///
+    /// # Arguments
+    ///
+    /// * `index` - Row or column number (1-based, valid values: 1 to 3).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`GameError::InvalidSquare`] if the number is outside the range 1..=3.
+    pub const fn new(index: usize) -> Result<Self, GameError> {
+        match index {
+            1..=3 => Ok(Self(index)),
+            _ => Err(GameError::InvalidSquare),
+        }
+    }
+
+    /// Returns the row or column number (1-based).
+    #[must_use]
+    pub const fn get(self) -> usize {
+        self.0
+    }
+}
+
+impl fmt::Display for LineIndex {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// A complete line on the board, along with the value of the squares that own it.
+///
+/// Returned by [`Board::winning_line`](crate::Board::winning_line) so that user interfaces can
+/// highlight the win and analysis code can explain the result.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct WinningLine {
+    /// The line that has been completed.
+    pub line:  Line,
+    /// The value held by all three squares on the line.
+    pub owner: SquareValue,
+}
+
+impl fmt::Display for Line {
+    /// Formats the line as a human readable name, such as `row 1` or `main diagonal`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Row(row) => write!(f, "row {row}"),
+            Self::Column(col) => write!(f, "column {col}"),
+            Self::MainDiagonal => write!(f, "main diagonal"),
+            Self::AntiDiagonal => write!(f, "anti-diagonal"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_all_lines_are_distinct() {
+        for (i, first) in Line::ALL.iter().enumerate() {
+            for second in &Line::ALL[i + 1..] {
+                assert_ne!(first, second);
+            }
+        }
+    }
+
+    #[test]
+    fn test_validated_constructors() {
+        assert_eq!(Line::row(2), Ok(Line::ALL[1]));
+        assert_eq!(Line::column(3), Ok(Line::ALL[5]));
+        assert_eq!(Line::row(0), Err(GameError::InvalidSquare));
+        assert_eq!(Line::column(4), Err(GameError::InvalidSquare));
+    }
+
+    #[test]
+    fn test_coords() {
+        let column = Line::column(2).unwrap();
+        assert_eq!(column.coords().map(Coord::row), [1, 2, 3]);
+        assert_eq!(column.coords().map(Coord::col), [2, 2, 2]);
+        assert_eq!(Line::MainDiagonal.coords()[2], Coord::new(3, 3).unwrap());
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!(Line::row(2).unwrap().to_string(), "row 2");
+        assert_eq!(Line::column(3).unwrap().to_string(), "column 3");
+        assert_eq!(Line::MainDiagonal.to_string(), "main diagonal");
+        assert_eq!(Line::AntiDiagonal.to_string(), "anti-diagonal");
+    }
+}
@@ -0,0 +1,430 @@
+// SPDX-FileCopyrightText: 2022 - 2024 Ali Sajid Imami
+//
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use std::time::{
+    Duration,
+    Instant,
+};
+
+use rand::{
+    seq::IndexedRandom,
+    Rng,
+    SeedableRng,
+};
+
+use crate::{
+    opponents::heuristic_move,
+    Agent,
+    Board,
+    Coord,
+    GameStatus,
+    Seeded,
+    SeededRng,
+    Symbol,
+};
+
+/// How the moves of a simulated game are chosen once the search leaves the tree.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum RolloutPolicy {
+    /// Both players play uniformly random legal moves.
+    #[default]
+    Random,
+    /// Both players win if they can, block if they must, and otherwise prefer the centre, then
+    /// corners, then edges, as [`HeuristicAgent`](crate::HeuristicAgent) does.
+    Heuristic,
+}
+
+/// The settings of an [`MctsAgent`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MctsConfig {
+    /// The maximum number of simulated games per move.
+    pub iterations:  u32,
+    /// The maximum time spent per move, or `None` for no limit. The search stops as soon as
+    /// either budget runs out, but always simulates at least one game so that a move is found.
+    pub time_budget: Option<Duration>,
+    /// The UCT exploration constant. Larger values try weaker moves more often.
+    pub exploration: f64,
+    /// How simulated games are played out beyond the tree.
+    pub rollout:     RolloutPolicy,
+}
+
+impl Default for MctsConfig {
+    /// Creates a configuration running 1000 random rollouts per move, with the textbook
+    /// exploration constant of `√2`.
+    fn default() -> Self {
+        Self {
+            iterations:  1_000,
+            time_budget: None,
+            exploration: std::f64::consts::SQRT_2,
+            rollout:     RolloutPolicy::Random,
+        }
+    }
+}
+
+/// The statistics gathered for one move at the root of the search.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MoveStats {
+    /// The move.
+    pub coord:  Coord,
+    /// The number of simulated games that started with this move.
+    pub visits: u32,
+    /// The average result of those games for the player to move, where a win is `1.0`, a draw
+    /// is `0.5` and a loss is `0.0`.
+    pub value:  f64,
+}
+
+/// The result of searching a position with Monte Carlo Tree Search.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MctsResult {
+    /// The most visited move, or `None` if the game is already over.
+    pub best_move:  Option<Coord>,
+    /// The number of simulated games played.
+    pub iterations: u32,
+    /// The statistics of every legal move, in row-major order.
+    pub moves:      Vec<MoveStats>,
+}
+
+/// A node of the search tree.
+#[derive(Debug, Clone)]
+struct Node {
+    /// The position at this node.
+    board:    Board,
+    /// The index of the parent node, or `None` for the root.
+    parent:   Option<usize>,
+    /// The move that led from the parent to this node, or `None` for the root.
+    coord:    Option<Coord>,
+    /// The indices of the expanded children.
+    children: Vec<usize>,
+    /// The legal moves that have not been expanded yet.
+    untried:  Vec<Coord>,
+    /// The number of simulated games that passed through this node.
+    visits:   u32,
+    /// The total result of those games for the player who moved into this node.
+    reward:   f64,
+}
+
+impl Node {
+    /// Creates an unvisited node.
+    fn new(board: Board, parent: Option<usize>, coord: Option<Coord>) -> Self {
+        Self {
+            board,
+            parent,
+            coord,
+            children: Vec::new(),
+            untried: board.legal_moves(),
+            visits: 0,
+            reward: 0.0,
+        }
+    }
+
+    /// Returns the average result for the player who moved into this node.
+    fn value(&self) -> f64 {
+        if self.visits == 0 {
+            0.0
+        } else {
+            self.reward / f64::from(self.visits)
+        }
+    }
+}
+
+/// An agent that chooses moves with Monte Carlo Tree Search.
+///
+/// Each move is chosen by growing a fresh search tree from the current position. Every
+/// iteration descends the tree by the UCT rule, expands one new position, plays a simulated
+/// game to the end using the [`RolloutPolicy`], and backs the result up the path. Unlike
+/// MENACE, nothing is remembered between moves or games.
+///
+/// # Examples
+///
+/// ```
+/// use tictacrustle::{
+///     BoardCodec,
+///     Coord,
+///     MctsAgent,
+///     MctsConfig,
+///     Seeded,
+/// };
+///
+/// let mut agent = MctsAgent::new(MctsConfig::default()).with_seed(7);
+///
+/// // X can win at once by completing the top row.
+/// let board = BoardCodec::new().decode("110220000").unwrap();
+/// let result = agent.search(&board);
+/// assert_eq!(result.best_move, Coord::new(1, 3).ok());
+/// ```
+#[derive(Debug, Clone)]
+pub struct MctsAgent {
+    /// The settings of the search.
+    config: MctsConfig,
+    /// The random number generator used for rollouts and tie-breaking.
+    rng:    SeededRng,
+}
+
+impl MctsAgent {
+    /// Creates a new `MctsAgent`.
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - The settings of the search.
+    #[must_use]
+    pub fn new(config: MctsConfig) -> Self {
+        Self {
+            config,
+            rng: SeededRng::from_os_rng(),
+        }
+    }
+
+    /// Returns the settings of the search.
+    #[must_use]
+    pub const fn config(&self) -> &MctsConfig {
+        &self.config
+    }
+
+    /// Searches a position for the best move of the player to move.
+    ///
+    /// # Arguments
+    ///
+    /// * `board` - The position to search.
+    pub fn search(&mut self, board: &Board) -> MctsResult {
+        let mut tree = vec![Node::new(*board, None, None)];
+        let started = Instant::now();
+        let mut iterations = 0;
+
+        while !tree[0].untried.is_empty() || !tree[0].children.is_empty() {
+            let spent = iterations >= self.config.iterations
+                || self
+                    .config
+                    .time_budget
+                    .is_some_and(|budget| started.elapsed() >= budget);
+            if iterations > 0 && spent {
+                break;
+            }
+            self.iterate(&mut tree);
+            iterations += 1;
+        }
+
+        let mut moves: Vec<MoveStats> = tree[0]
+            .children
+            .iter()
+            .filter_map(|&child| {
+                let node = &tree[child];
+                node.coord.map(|coord| MoveStats {
+                    coord,
+                    visits: node.visits,
+                    value: node.value(),
+                })
+            })
+            .collect();
+        moves.sort_by_key(|stats| stats.coord.index());
+
+        let best_move = moves
+            .iter()
+            .max_by(|a, b| a.visits.cmp(&b.visits).then(a.value.total_cmp(&b.value)))
+            .map(|stats| stats.coord);
+
+        MctsResult {
+            best_move,
+            iterations,
+            moves,
+        }
+    }
+
+    /// Runs one select, expand, simulate and backpropagate cycle.
+    fn iterate(&mut self, tree: &mut Vec<Node>) {
+        let mut index = 0;
+        while tree[index].untried.is_empty() && !tree[index].children.is_empty() {
+            index = self.select_child(tree, index);
+        }
+
+        if !tree[index].untried.is_empty() {
+            let pick = self.rng.random_range(0..tree[index].untried.len());
+            let coord = tree[index].untried.swap_remove(pick);
+            if let Ok(next) = tree[index].board.after_move(coord) {
+                tree.push(Node::new(next, Some(index), Some(coord)));
+                let child = tree.len() - 1;
+                tree[index].children.push(child);
+                index = child;
+            }
+        }
+
+        let winner = self.rollout(tree[index].board);
+        let mut current = Some(index);
+        while let Some(node_index) = current {
+            let node = &mut tree[node_index];
+            node.visits += 1;
+            // The player who moved into a node is the one not to move in it.
+            let mover = node.board.to_move().opponent();
+            node.reward += match winner {
+                Some(symbol) if symbol == mover => 1.0,
+                Some(_) => 0.0,
+                None => 0.5,
+            };
+            current = node.parent;
+        }
+    }
+
+    /// Picks the child of a fully expanded node with the highest UCT score.
+    fn select_child(&self, tree: &[Node], index: usize) -> usize {
+        let log_visits = f64::from(tree[index].visits.max(1)).ln();
+        let uct = |child: usize| {
+            let node = &tree[child];
+            if node.visits == 0 {
+                return f64::INFINITY;
+            }
+            node.value() + self.config.exploration * (log_visits / f64::from(node.visits)).sqrt()
+        };
+        tree[index]
+            .children
+            .iter()
+            .copied()
+            .max_by(|&a, &b| uct(a).total_cmp(&uct(b)))
+            .unwrap_or(index)
+    }
+
+    /// Plays a simulated game to the end and returns the winner, if any.
+    fn rollout(&mut self, mut board: Board) -> Option<Symbol> {
+        loop {
+            let to_move = match board.status() {
+                GameStatus::InProgress { to_move } => to_move,
+                status => return status.winner(),
+            };
+            let choice = match self.config.rollout {
+                RolloutPolicy::Random => board.legal_moves().choose(&mut self.rng).copied(),
+                RolloutPolicy::Heuristic => heuristic_move(&board, to_move, &mut self.rng),
+            };
+            match choice.map(|coord| board.after_move(coord)) {
+                Some(Ok(next)) => board = next,
+                _ => return None,
+            }
+        }
+    }
+}
+
+impl Seeded for MctsAgent {
+    fn rng_mut(&mut self) -> &mut SeededRng {
+        &mut self.rng
+    }
+}
+
+impl Agent for MctsAgent {
+    fn choose_move(&mut self, board: &Board, _symbol: Symbol) -> Option<Coord> {
+        self.search(board).best_move
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use rstest::rstest;
+
+    use super::*;
+    use crate::{
+        play_match,
+        BoardCodec,
+        GameResult,
+        RandomAgent,
+    };
+
+    fn seeded(rollout: RolloutPolicy, seed: u64) -> MctsAgent {
+        MctsAgent::new(MctsConfig {
+            rollout,
+            ..MctsConfig::default()
+        })
+        .with_seed(seed)
+    }
+
+    #[rstest]
+    #[case(RolloutPolicy::Random, "110220000", (1, 3))]
+    #[case(RolloutPolicy::Random, "100220100", (2, 3))]
+    #[case(RolloutPolicy::Heuristic, "110220000", (1, 3))]
+    #[case(RolloutPolicy::Heuristic, "100220100", (2, 3))]
+    fn test_finds_tactical_moves(
+        #[case] rollout: RolloutPolicy,
+        #[case] encoded: &str,
+        #[case] expected: (usize, usize),
+    ) {
+        let board = BoardCodec::new().decode(encoded).unwrap();
+        let result = seeded(rollout, 1).search(&board);
+        assert_eq!(result.best_move, Coord::new(expected.0, expected.1).ok());
+    }
+
+    #[test]
+    fn test_respects_iteration_budget() {
+        let mut agent = MctsAgent::new(MctsConfig {
+            iterations: 50,
+            ..MctsConfig::default()
+        })
+        .with_seed(3);
+        let result = agent.search(&Board::new());
+        assert_eq!(result.iterations, 50);
+        assert_eq!(
+            result.moves.iter().map(|stats| stats.visits).sum::<u32>(),
+            50
+        );
+        assert_eq!(result.moves.len(), 9);
+    }
+
+    #[test]
+    fn test_respects_time_budget() {
+        let mut agent = MctsAgent::new(MctsConfig {
+            iterations: u32::MAX,
+            time_budget: Some(Duration::from_millis(20)),
+            ..MctsConfig::default()
+        })
+        .with_seed(3);
+        let started = Instant::now();
+        let result = agent.search(&Board::new());
+        assert!(started.elapsed() < Duration::from_secs(5));
+        assert!(result.iterations > 0);
+    }
+
+    #[rstest]
+    #[case(0, None)]
+    #[case(1_000, Some(Duration::ZERO))]
+    fn test_zero_budget_still_moves(
+        #[case] iterations: u32,
+        #[case] time_budget: Option<Duration>,
+    ) {
+        let mut agent = MctsAgent::new(MctsConfig {
+            iterations,
+            time_budget,
+            ..MctsConfig::default()
+        })
+        .with_seed(3);
+        let board = Board::new();
+        let result = agent.search(&board);
+        assert_eq!(result.iterations, 1);
+        assert!(result.best_move.is_some());
+        assert!(agent.choose_move(&board, Symbol::X).is_some());
+    }
+
+    #[test]
+    fn test_finished_game_has_no_move() {
+        let board = BoardCodec::new().decode("111220000").unwrap();
+        let result = seeded(RolloutPolicy::Random, 1).search(&board);
+        assert_eq!(result.best_move, None);
+        assert_eq!(result.iterations, 0);
+    }
+
+    #[test]
+    fn test_same_seed_same_search() {
+        let first = seeded(RolloutPolicy::Random, 42).search(&Board::new());
+        let second = seeded(RolloutPolicy::Random, 42).search(&Board::new());
+        assert_eq!(first, second);
+    }
+
+    #[test]
+    fn test_rarely_loses_to_random_play() {
+        let mut agent = seeded(RolloutPolicy::Heuristic, 9);
+        let mut opponent = RandomAgent::new().with_seed(10);
+        let losses = (0..20)
+            .filter(|_| {
+                let record = play_match(&mut agent, &mut opponent).unwrap();
+                record.outcome.result_for(Symbol::X) == GameResult::Loss
+            })
+            .count();
+        assert!(losses <= 1);
+    }
+}
@@ -0,0 +1,567 @@
+// SPDX-FileCopyrightText: 2022 - 2024 Ali Sajid Imami
+//
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+mod agent;
+mod document;
+mod matchbox;
+mod policy;
+mod storage;
+
+use std::collections::HashMap;
+
+use rand::{
+    seq::IndexedRandom,
+    Rng,
+};
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+pub use self::{
+    agent::MenaceAgent,
+    document::FORMAT_VERSION,
+    matchbox::{
+        initial_beads,
+        Matchbox,
+    },
+    policy::{
+        EmptyBoxPolicy,
+        ReinforcementPolicy,
+        RewardSchedule,
+    },
+};
+use crate::{
+    Board,
+    BoardCodec,
+    Coord,
+    GameResult,
+    Transform,
+};
+
+/// The two flavours of MENACE described in the project README.
+///
+/// In saved documents the flavours are written as `"C"` and `"S"`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Flavor {
+    /// MENACE-C: boards that are rotations or reflections of each other share a matchbox, keyed
+    /// by their [`Board::canonical`] form, as in Michie's original design.
+    #[default]
+    #[serde(rename = "C")]
+    Classic,
+    /// MENACE-S: every board has its own matchbox, even if it is symmetric to another board.
+    #[serde(rename = "S")]
+    Symmetric,
+}
+
+/// The settings that decide how a MENACE learns.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct MenaceConfig {
+    /// Whether symmetric positions share a matchbox.
+    pub flavor:        Flavor,
+    /// How beads change once a game is over.
+    pub reinforcement: ReinforcementPolicy,
+    /// What happens when a matchbox runs out of beads.
+    pub empty_box:     EmptyBoxPolicy,
+}
+
+/// A single bead drawn by MENACE during a game.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BeadDraw {
+    /// The [`BoardCodec::to_number`] key of the matchbox the bead was drawn from.
+    pub matchbox: u16,
+    /// The move the bead stands for, on the board the matchbox is keyed by.
+    pub bead:     Coord,
+}
+
+/// The Machine Educable Noughts And Crosses Engine.
+///
+/// MENACE keeps one [`Matchbox`] per position it has been asked to play. How positions are keyed
+/// depends on its [`Flavor`]: MENACE-C keys matchboxes by the [`Board::canonical`] form of the
+/// position so that symmetric boards share a matchbox, while MENACE-S keys them by the board as
+/// played. Both flavours expose the same API, so they can be trained side by side. Matchboxes
+/// are created lazily the first time a position is seen, with beads for each legal move
+/// following the [`initial_beads`] schedule.
+///
+/// During a game every bead drawn is recorded. Once the game is over, [`Menace::reinforce`]
+/// applies the [`ReinforcementPolicy`] to each drawn bead. By default this is Michie's
+/// reinforcement: 3 beads are added for a win, 1 for a draw, and 1 is taken away for a loss.
+///
+/// # Examples
+///
+/// ```
+/// use rand::SeedableRng;
+/// use tictacrustle::{
+///     Board,
+///     GameResult,
+///     Menace,
+///     SeededRng,
+/// };
+///
+/// let mut rng = SeededRng::seed_from_u64(42);
+/// let mut menace = Menace::new();
+///
+/// let board = Board::new();
+/// let coord = menace.choose_move(&board, &mut rng).unwrap();
+/// assert!(board.square(coord).is_empty());
+///
+/// menace.reinforce(GameResult::Win);
+/// assert_eq!(menace.matchbox(&board).unwrap().total(), 9 * 4 + 3);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Menace {
+    /// The settings that decide how MENACE learns.
+    config:       MenaceConfig,
+    /// The matchboxes, keyed by the base-3 number of their board.
+    boxes:        HashMap<u16, Matchbox>,
+    /// The beads drawn during the current game, in the order they were drawn.
+    history:      Vec<BeadDraw>,
+    /// The number of games that have been reinforced.
+    games_played: u64,
+}
+
+impl Menace {
+    /// Creates a new MENACE-C with no matchboxes.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a new MENACE of the given flavour with no matchboxes.
+    ///
+    /// # Arguments
+    ///
+    /// * `flavor` - Whether symmetric positions share a matchbox (MENACE-C) or not (MENACE-S).
+    #[must_use]
+    pub fn with_flavor(flavor: Flavor) -> Self {
+        Self::with_config(MenaceConfig {
+            flavor,
+            ..MenaceConfig::default()
+        })
+    }
+
+    /// Creates a new MENACE with the given settings and no matchboxes.
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - The flavour, reinforcement policy and empty matchbox policy to use.
+    #[must_use]
+    pub fn with_config(config: MenaceConfig) -> Self {
+        Self {
+            config,
+            ..Self::default()
+        }
+    }
+
+    /// Returns the flavour of this MENACE.
+    #[must_use]
+    pub const fn flavor(&self) -> Flavor {
+        self.config.flavor
+    }
+
+    /// Returns the settings of this MENACE.
+    #[must_use]
+    pub const fn config(&self) -> &MenaceConfig {
+        &self.config
+    }
+
+    /// Chooses a move for the position by drawing a bead from its matchbox.
+    ///
+    /// The matchbox is created if this is the first time the position has been seen. The drawn
+    /// bead is recorded so the move can be reinforced once the game is over.
+    ///
+    /// # Arguments
+    ///
+    /// * `board` - The position MENACE has to play.
+    /// * `rng` - The random number generator used for the draw.
+    ///
+    /// If the matchbox has run out of beads, the [`EmptyBoxPolicy`] decides what happens.
+    ///
+    /// # Returns
+    ///
+    /// The chosen move on `board`, or `None` if MENACE resigns or the game is already over.
+    pub fn choose_move<R: Rng + ?Sized>(&mut self, board: &Board, rng: &mut R) -> Option<Coord> {
+        if board.status().is_over() {
+            return None;
+        }
+        let (keyed, transform) = self.keyed_board(board);
+        let key = BoardCodec::new().to_number(&keyed);
+        let initial = Matchbox::new(&keyed, initial_beads(keyed.ply()));
+        let matchbox = self.boxes.entry(key).or_insert(initial);
+
+        let bead = match (matchbox.is_empty(), self.config.empty_box) {
+            (false, _) => matchbox.draw(rng)?,
+            (true, EmptyBoxPolicy::Resign) => return None,
+            (true, EmptyBoxPolicy::Refill) => {
+                *matchbox = initial;
+                matchbox.draw(rng)?
+            }
+            (true, EmptyBoxPolicy::UniformRandom) => *keyed.legal_moves().choose(rng)?,
+        };
+
+        self.history.push(BeadDraw {
+            matchbox: key,
+            bead,
+        });
+        Some(bead.transform(transform.inverse()))
+    }
+
+    /// Applies reinforcement to every bead drawn during the current game and starts a new game.
+    ///
+    /// # Arguments
+    ///
+    /// * `result` - The result of the game from MENACE's point of view.
+    pub fn reinforce(&mut self, result: GameResult) {
+        let moves = self.history.len();
+        for (index, draw) in self.history.drain(..).enumerate() {
+            if let Some(matchbox) = self.boxes.get_mut(&draw.matchbox) {
+                self.config
+                    .reinforcement
+                    .apply(matchbox, draw.bead, result, moves - index - 1);
+            }
+        }
+        self.games_played += 1;
+    }
+
+    /// Returns the matchbox for a position, if MENACE has seen it.
+    ///
+    /// For MENACE-C the matchbox is keyed by the canonical form of the position, so its beads are
+    /// indexed by squares of [`Board::canonical`] rather than of `board`.
+    #[must_use]
+    pub fn matchbox(&self, board: &Board) -> Option<&Matchbox> {
+        self.boxes.get(&self.key(board))
+    }
+
+    /// Returns the key of the matchbox for a position: the [`BoardCodec::to_number`] of the
+    /// canonical board for MENACE-C, or of the board itself for MENACE-S.
+    #[must_use]
+    pub fn key(&self, board: &Board) -> u16 {
+        BoardCodec::new().to_number(&self.keyed_board(board).0)
+    }
+
+    /// Returns every matchbox along with its key, in no particular order.
+    pub fn boxes(&self) -> impl Iterator<Item = (u16, &Matchbox)> {
+        self.boxes.iter().map(|(&key, matchbox)| (key, matchbox))
+    }
+
+    /// Returns the number of beads in all matchboxes together.
+    #[must_use]
+    pub fn total_beads(&self) -> u64 {
+        self.boxes
+            .values()
+            .map(|matchbox| u64::from(matchbox.total()))
+            .sum()
+    }
+
+    /// Returns the beads drawn so far in the current game.
+    #[must_use]
+    pub fn history(&self) -> &[BeadDraw] {
+        &self.history
+    }
+
+    /// Removes and returns the beads drawn so far in the current game.
+    ///
+    /// Together with [`Menace::restore_history`] this lets one MENACE play several games at
+    /// once: each game keeps its own draws and hands them back before MENACE moves or is
+    /// reinforced.
+    pub fn take_history(&mut self) -> Vec<BeadDraw> {
+        std::mem::take(&mut self.history)
+    }
+
+    /// Replaces the beads drawn in the current game, typically with draws saved by
+    /// [`Menace::take_history`].
+    ///
+    /// # Arguments
+    ///
+    /// * `history` - The beads drawn in the game about to continue.
+    pub fn restore_history(&mut self, history: Vec<BeadDraw>) {
+        self.history = history;
+    }
+
+    /// Returns the number of games that have been reinforced.
+    #[must_use]
+    pub const fn games_played(&self) -> u64 {
+        self.games_played
+    }
+
+    /// Returns the number of matchboxes MENACE has created.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.boxes.len()
+    }
+
+    /// Checks whether MENACE has not created any matchboxes yet.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.boxes.is_empty()
+    }
+
+    /// Maps a position to the board its matchbox is keyed by, along with the transform that
+    /// produces that board from `board`.
+    fn keyed_board(&self, board: &Board) -> (Board, Transform) {
+        match self.config.flavor {
+            Flavor::Classic => board.canonical(),
+            Flavor::Symmetric => (*board, Transform::Identity),
+        }
+    }
+
+    /// Checks that a saved matchbox belongs to a reachable, unfinished board that MENACE keys its
+    /// boxes by, has no beads for squares that are taken, and holds no more beads in total than
+    /// [`Matchbox::total`] can count.
+    fn is_valid_matchbox(&self, board: &Board, matchbox: &Matchbox) -> bool {
+        let beads_on_taken_squares = Coord::all()
+            .any(|coord| matchbox.beads_for(coord) > 0 && !board.square(coord).is_empty());
+        let total_fits = matchbox
+            .beads()
+            .into_iter()
+            .try_fold(0_u32, u32::checked_add)
+            .is_some();
+        board.is_reachable()
+            && !board.status().is_over()
+            && !beads_on_taken_squares
+            && total_fits
+            && self.key(board) == BoardCodec::new().to_number(board)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+
+    use super::*;
+    use crate::{
+        SeededRng,
+        Transform,
+    };
+
+    #[test]
+    fn test_choose_move_creates_matchbox() {
+        let mut rng = SeededRng::seed_from_u64(1);
+        let mut menace = Menace::new();
+        let board = Board::new();
+
+        assert!(menace.is_empty());
+        menace.choose_move(&board, &mut rng).unwrap();
+
+        assert_eq!(menace.len(), 1);
+        assert_eq!(menace.history().len(), 1);
+        assert_eq!(menace.matchbox(&board).unwrap().total(), 9 * 4);
+    }
+
+    #[test]
+    fn test_finished_game_creates_no_matchbox() {
+        let mut rng = SeededRng::seed_from_u64(1);
+        let mut menace = Menace::new();
+        let mut board = Board::new();
+        for col in 1..=3 {
+            board.get_square_mut(1, col).set_x();
+        }
+        board.get_square_mut(2, 1).set_o();
+        board.get_square_mut(2, 2).set_o();
+
+        assert_eq!(menace.choose_move(&board, &mut rng), None);
+        assert!(menace.is_empty());
+        assert!(menace.history().is_empty());
+    }
+
+    #[test]
+    fn test_symmetric_positions_share_matchbox() {
+        let mut rng = SeededRng::seed_from_u64(2);
+        let mut menace = Menace::new();
+        let mut board = Board::new();
+        board.get_square_mut(1, 1).set_x();
+
+        for transform in Transform::ALL {
+            let coord = menace
+                .choose_move(&board.transform(transform), &mut rng)
+                .unwrap();
+            assert!(board.transform(transform).square(coord).is_empty());
+        }
+        assert_eq!(menace.len(), 1);
+        assert_eq!(menace.matchbox(&board).unwrap().total(), 8 * 4);
+    }
+
+    #[test]
+    fn test_symmetric_flavor_keeps_positions_apart() {
+        let mut rng = SeededRng::seed_from_u64(2);
+        let mut menace = Menace::with_flavor(Flavor::Symmetric);
+        let mut board = Board::new();
+        board.get_square_mut(1, 1).set_x();
+
+        for transform in Transform::ALL {
+            let transformed = board.transform(transform);
+            let coord = menace.choose_move(&transformed, &mut rng).unwrap();
+            assert!(transformed.square(coord).is_empty());
+        }
+        // The X in the corner can only be in one of four corners.
+        assert_eq!(menace.flavor(), Flavor::Symmetric);
+        assert_eq!(menace.len(), 4);
+        assert!(menace
+            .matchbox(&board.transform(Transform::Rotate90))
+            .is_some());
+    }
+
+    #[test]
+    fn test_flavors_reinforce_alike() {
+        let mut rng = SeededRng::seed_from_u64(5);
+        let board = Board::new();
+        for flavor in [Flavor::Classic, Flavor::Symmetric] {
+            let mut menace = Menace::with_flavor(flavor);
+            menace.choose_move(&board, &mut rng).unwrap();
+            menace.reinforce(GameResult::Win);
+            assert_eq!(menace.matchbox(&board).unwrap().total(), 39);
+        }
+    }
+
+    #[test]
+    fn test_reinforce_rewards_drawn_beads() {
+        let mut rng = SeededRng::seed_from_u64(3);
+        let board = Board::new();
+
+        for (result, expected) in [
+            (GameResult::Win, 39),
+            (GameResult::Draw, 37),
+            (GameResult::Loss, 35),
+        ] {
+            let mut menace = Menace::new();
+            menace.choose_move(&board, &mut rng).unwrap();
+            menace.reinforce(result);
+
+            assert_eq!(menace.matchbox(&board).unwrap().total(), expected);
+            assert!(menace.history().is_empty());
+            assert_eq!(menace.games_played(), 1);
+        }
+    }
+
+    #[test]
+    fn test_reinforce_uses_policy() {
+        let mut rng = SeededRng::seed_from_u64(6);
+        let mut menace = Menace::with_config(MenaceConfig {
+            reinforcement: ReinforcementPolicy {
+                schedule:  RewardSchedule::Discounted {
+                    win:    4,
+                    draw:   2,
+                    loss:   -2,
+                    factor: 0.5,
+                },
+                min_beads: 0,
+                max_beads: None,
+            },
+            ..MenaceConfig::default()
+        });
+        let first = Board::new();
+        let coord = menace.choose_move(&first, &mut rng).unwrap();
+        let second = first
+            .after_move(coord)
+            .unwrap()
+            .after_move(
+                *first
+                    .after_move(coord)
+                    .unwrap()
+                    .legal_moves()
+                    .first()
+                    .unwrap(),
+            )
+            .unwrap();
+        menace.choose_move(&second, &mut rng).unwrap();
+        menace.reinforce(GameResult::Win);
+
+        // The last move gets the full reward, the one before it half of it.
+        assert_eq!(menace.matchbox(&first).unwrap().total(), 9 * 4 + 2);
+        assert_eq!(menace.matchbox(&second).unwrap().total(), 7 * 3 + 4);
+    }
+
+    #[test]
+    fn test_empty_matchbox_refills() {
+        let mut rng = SeededRng::seed_from_u64(4);
+        let mut menace = Menace::with_config(MenaceConfig {
+            empty_box: EmptyBoxPolicy::Refill,
+            ..MenaceConfig::default()
+        });
+        let board = BoardCodec::new().decode("121212210").unwrap();
+
+        menace.choose_move(&board, &mut rng).unwrap();
+        menace.reinforce(GameResult::Loss);
+        assert!(menace.matchbox(&board).unwrap().is_empty());
+
+        assert!(menace.choose_move(&board, &mut rng).is_some());
+        assert_eq!(menace.matchbox(&board).unwrap().total(), 1);
+    }
+
+    #[test]
+    fn test_empty_matchbox_picks_uniformly() {
+        let mut rng = SeededRng::seed_from_u64(4);
+        let mut menace = Menace::with_config(MenaceConfig {
+            empty_box: EmptyBoxPolicy::UniformRandom,
+            ..MenaceConfig::default()
+        });
+        let board = BoardCodec::new().decode("121212210").unwrap();
+
+        menace.choose_move(&board, &mut rng).unwrap();
+        menace.reinforce(GameResult::Loss);
+
+        assert_eq!(menace.choose_move(&board, &mut rng), Coord::new(3, 3).ok());
+        menace.reinforce(GameResult::Win);
+        assert_eq!(menace.matchbox(&board).unwrap().total(), 3);
+    }
+
+    #[test]
+    fn test_empty_matchbox_resigns() {
+        let mut rng = SeededRng::seed_from_u64(4);
+        let mut menace = Menace::new();
+        // A position with a single legal move, which gets a single bead.
+        let board = BoardCodec::new().decode("121212210").unwrap();
+
+        assert!(menace.choose_move(&board, &mut rng).is_some());
+        menace.reinforce(GameResult::Loss);
+        assert!(menace.matchbox(&board).unwrap().is_empty());
+        assert_eq!(menace.choose_move(&board, &mut rng), None);
+    }
+
+    #[test]
+    fn test_keys_and_bead_totals() {
+        let mut rng = SeededRng::seed_from_u64(5);
+        let mut classic = Menace::new();
+        let mut symmetric = Menace::with_flavor(Flavor::Symmetric);
+        let corner = BoardCodec::new().decode("100000000").unwrap();
+        let other_corner = corner.transform(Transform::Rotate90);
+
+        assert_eq!(classic.key(&corner), classic.key(&other_corner));
+        assert_ne!(symmetric.key(&corner), symmetric.key(&other_corner));
+
+        classic.choose_move(&Board::new(), &mut rng).unwrap();
+        classic.choose_move(&corner, &mut rng).unwrap();
+        assert_eq!(classic.total_beads(), 9 * 4 + 8 * 4);
+        assert_eq!(classic.boxes().count(), 2);
+
+        symmetric.choose_move(&corner, &mut rng).unwrap();
+        let (key, matchbox) = symmetric.boxes().next().unwrap();
+        assert_eq!(key, symmetric.key(&corner));
+        assert_eq!(matchbox.total(), 8 * 4);
+    }
+
+    #[test]
+    fn test_interleaved_games() {
+        let mut rng = SeededRng::seed_from_u64(6);
+        let mut menace = Menace::new();
+
+        menace.choose_move(&Board::new(), &mut rng).unwrap();
+        let first = menace.take_history();
+        let corner = BoardCodec::new().decode("100000000").unwrap();
+        menace.choose_move(&corner, &mut rng).unwrap();
+        let second = menace.take_history();
+        assert!(menace.history().is_empty());
+
+        menace.restore_history(first.clone());
+        assert_eq!(menace.history(), first.as_slice());
+        menace.reinforce(GameResult::Win);
+        assert_eq!(menace.matchbox(&Board::new()).unwrap().total(), 9 * 4 + 3);
+        assert_eq!(menace.matchbox(&corner).unwrap().total(), 8 * 4);
+
+        menace.restore_history(second);
+        menace.reinforce(GameResult::Loss);
+        assert_eq!(menace.matchbox(&corner).unwrap().total(), 8 * 4 - 1);
+    }
+}
@@ -0,0 +1,144 @@
+// SPDX-FileCopyrightText: 2022 - 2024 Ali Sajid Imami
+//
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use rand::SeedableRng;
+
+use crate::{
+    Agent,
+    Board,
+    Coord,
+    LearnerStats,
+    MatchOutcome,
+    MatchRecord,
+    Menace,
+    Reportable,
+    Seeded,
+    SeededRng,
+    Symbol,
+};
+
+/// Lets a [`Menace`] play through the [`Agent`] trait.
+///
+/// Moves are drawn from MENACE's matchboxes, and the drawn beads are reinforced as soon as the
+/// game is over.
+#[derive(Debug, Clone)]
+pub struct MenaceAgent {
+    /// The engine that chooses and learns moves.
+    menace: Menace,
+    /// The random number generator used for bead draws.
+    rng:    SeededRng,
+}
+
+impl MenaceAgent {
+    /// Creates a new `MenaceAgent` seeded from the operating system.
+    ///
+    /// Use [`Seeded::with_seed`] to make its bead draws reproducible.
+    ///
+    /// # Arguments
+    ///
+    /// * `menace` - The engine that chooses and learns moves.
+    #[must_use]
+    pub fn new(menace: Menace) -> Self {
+        Self {
+            menace,
+            rng: SeededRng::from_os_rng(),
+        }
+    }
+
+    /// Returns the engine driving this agent.
+    #[must_use]
+    pub const fn menace(&self) -> &Menace {
+        &self.menace
+    }
+
+    /// Consumes the agent and returns the engine driving it.
+    #[must_use]
+    pub fn into_menace(self) -> Menace {
+        self.menace
+    }
+}
+
+impl Seeded for MenaceAgent {
+    fn rng_mut(&mut self) -> &mut SeededRng {
+        &mut self.rng
+    }
+}
+
+impl Agent for MenaceAgent {
+    fn choose_move(&mut self, board: &Board, _symbol: Symbol) -> Option<Coord> {
+        self.menace.choose_move(board, &mut self.rng)
+    }
+
+    fn on_game_end(&mut self, outcome: &MatchOutcome, symbol: Symbol) {
+        self.menace.reinforce(outcome.result_for(symbol));
+    }
+}
+
+impl Reportable for MenaceAgent {
+    fn stats(&self) -> Option<LearnerStats> {
+        Some(LearnerStats {
+            entries: self.menace.len(),
+            beads:   Some(self.menace.total_beads()),
+        })
+    }
+
+    /// Returns the keys of the matchboxes MENACE drew from during a game.
+    fn positions_used(&self, record: &MatchRecord, symbol: Symbol) -> Vec<u16> {
+        let mut board = Board::new();
+        let mut keys = Vec::new();
+        for &coord in &record.moves {
+            if board.to_move() == symbol {
+                keys.push(self.menace.key(&board));
+            }
+            let Ok(next) = board.after_move(coord) else {
+                break;
+            };
+            board = next;
+        }
+        keys
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        play_match,
+        RandomAgent,
+    };
+
+    #[test]
+    fn test_menace_learns_from_matches() {
+        let mut menace = MenaceAgent::new(Menace::new()).with_seed(1);
+        let mut opponent = RandomAgent::new().with_seed(2);
+
+        for _ in 0..20 {
+            play_match(&mut menace, &mut opponent).unwrap();
+            assert!(menace.menace().history().is_empty());
+        }
+        assert_eq!(menace.menace().games_played(), 20);
+        assert!(!menace.into_menace().is_empty());
+    }
+
+    #[test]
+    fn test_reports_matchboxes() {
+        let menace = MenaceAgent::new(Menace::new()).with_seed(1);
+        assert_eq!(
+            menace.stats(),
+            Some(LearnerStats {
+                entries: 0,
+                beads:   Some(0),
+            })
+        );
+
+        let record = MatchRecord {
+            outcome: crate::MatchOutcome::Resigned(Symbol::X),
+            moves:   vec![Coord::new(1, 1).unwrap(), Coord::new(3, 3).unwrap()],
+            board:   Board::new(),
+        };
+        assert_eq!(menace.positions_used(&record, Symbol::X).len(), 1);
+        assert_eq!(menace.positions_used(&record, Symbol::O).len(), 1);
+    }
+}
@@ -0,0 +1,305 @@
+// SPDX-FileCopyrightText: 2022 - 2024 Ali Sajid Imami
+//
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::Path,
+};
+
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+use super::{
+    EmptyBoxPolicy,
+    Flavor,
+    Matchbox,
+    Menace,
+    MenaceConfig,
+    ReinforcementPolicy,
+};
+use crate::{
+    BoardCodec,
+    PersistenceError,
+};
+
+/// The version of the JSON document written by [`Menace::to_json`].
+///
+/// It is increased whenever the layout of the document changes, so older libraries refuse
+/// documents they would misread.
+pub const FORMAT_VERSION: u32 = 1;
+
+/// The JSON document a trained MENACE is saved as.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct MenaceDocument {
+    /// The version of the document layout.
+    format_version: u32,
+    /// Whether symmetric positions share a matchbox.
+    flavor:         Flavor,
+    /// How beads change once a game is over.
+    reinforcement:  ReinforcementPolicy,
+    /// What happens when a matchbox runs out of beads.
+    empty_box:      EmptyBoxPolicy,
+    /// The number of games that have been reinforced.
+    games_played:   u64,
+    /// The bead counts of every matchbox, indexed by [`Coord::index`] and keyed by the
+    /// [`BoardCodec::encode`] string of the board the matchbox is keyed by.
+    boxes:          BTreeMap<String, [u32; 9]>,
+}
+
+impl Menace {
+    /// Saves MENACE as a pretty-printed JSON document.
+    ///
+    /// The document records the [`FORMAT_VERSION`], the flavour (`"C"` or `"S"`), the
+    /// reinforcement and empty matchbox policies, the number of games played, and the beads of
+    /// every matchbox keyed by its board code, such as `"100020000"`. The beads drawn during a
+    /// game in progress are not saved.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`PersistenceError::Json`] if the document cannot be serialised.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rand::SeedableRng;
+    /// use tictacrustle::{
+    ///     Board,
+    ///     GameResult,
+    ///     Menace,
+    ///     SeededRng,
+    /// };
+    ///
+    /// let mut rng = SeededRng::seed_from_u64(1);
+    /// let mut menace = Menace::new();
+    /// menace.choose_move(&Board::new(), &mut rng).unwrap();
+    /// menace.reinforce(GameResult::Draw);
+    ///
+    /// let json = menace.to_json().unwrap();
+    /// assert!(json.contains("\"flavor\": \"C\""));
+    /// assert_eq!(Menace::from_json(&json).unwrap(), menace);
+    /// ```
+    pub fn to_json(&self) -> Result<String, PersistenceError> {
+        let codec = BoardCodec::new();
+        let boxes = self
+            .boxes
+            .iter()
+            .filter_map(|(&key, matchbox)| {
+                let board = codec.from_number(key).ok()?;
+                Some((codec.encode(&board), matchbox.beads()))
+            })
+            .collect();
+
+        let document = MenaceDocument {
+            format_version: FORMAT_VERSION,
+            flavor: self.config.flavor,
+            reinforcement: self.config.reinforcement,
+            empty_box: self.config.empty_box,
+            games_played: self.games_played,
+            boxes,
+        };
+        Ok(serde_json::to_string_pretty(&document)?)
+    }
+
+    /// Loads MENACE from a JSON document written by [`Menace::to_json`].
+    ///
+    /// # Arguments
+    ///
+    /// * `json` - The document to load.
+    ///
+    /// # Errors
+    ///
+    /// - [`PersistenceError::Json`] if the document is not valid JSON or lacks a field.
+    /// - [`PersistenceError::UnsupportedVersion`] if the document has a different format version.
+    /// - [`PersistenceError::InvalidMatchbox`] if a matchbox key is not a reachable board code, is
+    ///   a finished game, is not canonical for MENACE-C, or has beads for squares that are taken.
+    pub fn from_json(json: &str) -> Result<Self, PersistenceError> {
+        let document: MenaceDocument = serde_json::from_str(json)?;
+        if document.format_version != FORMAT_VERSION {
+            return Err(PersistenceError::UnsupportedVersion(
+                document.format_version,
+            ));
+        }
+
+        let mut menace = Self::with_config(MenaceConfig {
+            flavor:        document.flavor,
+            reinforcement: document.reinforcement,
+            empty_box:     document.empty_box,
+        });
+        menace.games_played = document.games_played;
+
+        let codec = BoardCodec::new();
+        for (code, beads) in document.boxes {
+            let invalid = || PersistenceError::InvalidMatchbox(code.clone());
+            let board = codec.decode(&code).map_err(|_| invalid())?;
+            let matchbox = Matchbox::from_beads(beads);
+            if !menace.is_valid_matchbox(&board, &matchbox) {
+                return Err(invalid());
+            }
+            menace.boxes.insert(codec.to_number(&board), matchbox);
+        }
+
+        Ok(menace)
+    }
+
+    /// Saves MENACE to a JSON file, replacing the file if it exists.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The file to write.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`PersistenceError::Io`] if the file cannot be written, or any error from
+    /// [`Menace::to_json`].
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), PersistenceError> {
+        fs::write(path, self.to_json()?)?;
+        Ok(())
+    }
+
+    /// Loads MENACE from a JSON file written by [`Menace::save`].
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The file to read.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`PersistenceError::Io`] if the file cannot be read, or any error from
+    /// [`Menace::from_json`].
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, PersistenceError> {
+        Self::from_json(&fs::read_to_string(path)?)
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use rand::SeedableRng;
+    use rstest::rstest;
+
+    use super::*;
+    use crate::{
+        play_match,
+        MenaceAgent,
+        RandomAgent,
+        RewardSchedule,
+        Seeded,
+        SeededRng,
+    };
+
+    fn trained(flavor: Flavor) -> Menace {
+        let mut learner = MenaceAgent::new(Menace::with_config(MenaceConfig {
+            flavor,
+            reinforcement: ReinforcementPolicy {
+                schedule:  RewardSchedule::Discounted {
+                    win:    3,
+                    draw:   1,
+                    loss:   -1,
+                    factor: 0.9,
+                },
+                min_beads: 1,
+                max_beads: Some(50),
+            },
+            empty_box: EmptyBoxPolicy::Refill,
+        }))
+        .with_rng(SeededRng::seed_from_u64(1));
+        let mut opponent = RandomAgent::new().with_seed(2);
+        for _ in 0..50 {
+            play_match(&mut learner, &mut opponent).unwrap();
+        }
+        learner.into_menace()
+    }
+
+    #[rstest]
+    #[case(Flavor::Classic)]
+    #[case(Flavor::Symmetric)]
+    fn test_round_trip(#[case] flavor: Flavor) {
+        let menace = trained(flavor);
+        let loaded = Menace::from_json(&menace.to_json().unwrap()).unwrap();
+        assert_eq!(loaded, menace);
+        assert_eq!(loaded.games_played(), 50);
+    }
+
+    #[test]
+    fn test_save_and_load() {
+        let menace = trained(Flavor::Classic);
+        let path = std::env::temp_dir().join(format!("menace-{}.json", std::process::id()));
+        menace.save(&path).unwrap();
+        let loaded = Menace::load(&path);
+        fs::remove_file(&path).unwrap();
+        assert_eq!(loaded.unwrap(), menace);
+    }
+
+    #[test]
+    fn test_document_layout() {
+        let json = trained(Flavor::Symmetric).to_json().unwrap();
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(value["format_version"], FORMAT_VERSION);
+        assert_eq!(value["flavor"], "S");
+        assert_eq!(value["empty_box"], "refill");
+        assert_eq!(value["reinforcement"]["schedule"]["type"], "discounted");
+        assert_eq!(value["boxes"]["000000000"].as_array().unwrap().len(), 9);
+    }
+
+    #[rstest]
+    #[case(0)]
+    #[case(2)]
+    fn test_rejects_other_versions(#[case] version: u32) {
+        let mut document: serde_json::Value =
+            serde_json::from_str(&Menace::new().to_json().unwrap()).unwrap();
+        document["format_version"] = serde_json::json!(version);
+        assert_eq!(
+            Menace::from_json(&document.to_string()),
+            Err(PersistenceError::UnsupportedVersion(version))
+        );
+    }
+
+    #[rstest]
+    #[case("C", "12000000x", [0; 9])]
+    #[case("C", "001000000", [0; 9])]
+    #[case("S", "111222000", [0; 9])]
+    #[case("S", "111220000", [0; 9])]
+    #[case("S", "121121212", [0; 9])]
+    #[case("C", "000000000", [u32::MAX, 1, 0, 0, 0, 0, 0, 0, 0])]
+    fn test_rejects_invalid_matchboxes(
+        #[case] flavor: &str,
+        #[case] code: &str,
+        #[case] beads: [u32; 9],
+    ) {
+        let json = format!(
+            r#"{{"format_version": 1, "flavor": "{flavor}", "reinforcement": {{"schedule": {{"type": "fixed", "win": 3, "draw": 1, "loss": -1}}, "min_beads": 0, "max_beads": null}}, "empty_box": "resign", "games_played": 0, "boxes": {{"{code}": {beads:?}}}}}"#
+        );
+        assert_eq!(
+            Menace::from_json(&json),
+            Err(PersistenceError::InvalidMatchbox(code.to_string()))
+        );
+    }
+
+    #[test]
+    fn test_rejects_beads_on_taken_squares() {
+        let mut document: serde_json::Value =
+            serde_json::from_str(&Menace::new().to_json().unwrap()).unwrap();
+        document["boxes"]["100000000"] = serde_json::json!([1, 1, 1, 1, 1, 1, 1, 1, 1]);
+        assert_eq!(
+            Menace::from_json(&document.to_string()),
+            Err(PersistenceError::InvalidMatchbox("100000000".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_rejects_malformed_json() {
+        assert!(matches!(
+            Menace::from_json("{"),
+            Err(PersistenceError::Json(_))
+        ));
+        assert_eq!(
+            Menace::load("/nonexistent/menace.json"),
+            Err(PersistenceError::Io(std::io::ErrorKind::NotFound))
+        );
+    }
+}
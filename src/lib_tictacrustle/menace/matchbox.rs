@@ -0,0 +1,192 @@
+// SPDX-FileCopyrightText: 2022 - 2024 Ali Sajid Imami
+//
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use rand::Rng;
+
+use crate::{
+    Board,
+    Coord,
+};
+
+/// Returns the number of beads placed for each legal move in a new matchbox.
+///
+/// Michie's schedule gives 4 beads per move for MENACE's first move, 3 for its second, 2 for its
+/// third and 1 for every later move, so that early moves, which are played in every game, take
+/// longer to be ruled out.
+///
+/// # Arguments
+///
+/// * `ply` - The number of moves already played on the board the matchbox belongs to.
+#[must_use]
+pub const fn initial_beads(ply: usize) -> u32 {
+    match ply / 2 {
+        0 => 4,
+        1 => 3,
+        2 => 2,
+        _ => 1,
+    }
+}
+
+/// A single MENACE matchbox, holding beads for the moves available in one position.
+///
+/// Beads are indexed by the [`Coord::index`] of the square they stand for. Squares that are not
+/// legal moves in the position never hold beads.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Matchbox {
+    /// The number of beads for each square of the board.
+    beads: [u32; 9],
+}
+
+impl Matchbox {
+    /// Creates a matchbox for the given position, with the same number of beads for each legal
+    /// move.
+    ///
+    /// # Arguments
+    ///
+    /// * `board` - The position the matchbox belongs to.
+    /// * `beads_per_move` - The number of beads to place for each legal move.
+    #[must_use]
+    pub fn new(board: &Board, beads_per_move: u32) -> Self {
+        let mut beads = [0; 9];
+        for coord in board.legal_moves() {
+            beads[coord.index()] = beads_per_move;
+        }
+        Self { beads }
+    }
+
+    /// Creates a matchbox from explicit bead counts, indexed by [`Coord::index`].
+    #[must_use]
+    pub const fn from_beads(beads: [u32; 9]) -> Self {
+        Self { beads }
+    }
+
+    /// Returns the bead counts, indexed by [`Coord::index`].
+    #[must_use]
+    pub const fn beads(&self) -> [u32; 9] {
+        self.beads
+    }
+
+    /// Returns the number of beads for a single move.
+    #[must_use]
+    pub const fn beads_for(&self, coord: Coord) -> u32 {
+        self.beads[coord.index()]
+    }
+
+    /// Returns the total number of beads in the matchbox.
+    #[must_use]
+    pub fn total(&self) -> u32 {
+        self.beads.iter().sum()
+    }
+
+    /// Checks whether the matchbox has run out of beads.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.total() == 0
+    }
+
+    /// Draws a bead at random, weighted by the number of beads for each move.
+    ///
+    /// The bead is not removed from the matchbox; the outcome of the game decides what happens
+    /// to it through [`Matchbox::adjust`].
+    ///
+    /// # Arguments
+    ///
+    /// * `rng` - The random number generator used for the draw.
+    ///
+    /// # Returns
+    ///
+    /// The move the bead stands for, or `None` if the matchbox is empty.
+    pub fn draw<R: Rng + ?Sized>(&self, rng: &mut R) -> Option<Coord> {
+        let total = self.total();
+        if total == 0 {
+            return None;
+        }
+
+        let mut pick = rng.random_range(0..total);
+        for (index, &count) in self.beads.iter().enumerate() {
+            if pick < count {
+                return Coord::from_index(index).ok();
+            }
+            pick -= count;
+        }
+        None
+    }
+
+    /// Sets the number of beads for a single move.
+    ///
+    /// # Arguments
+    ///
+    /// * `coord` - The move whose beads are set.
+    /// * `count` - The new number of beads.
+    pub const fn set_beads(&mut self, coord: Coord, count: u32) {
+        self.beads[coord.index()] = count;
+    }
+
+    /// Adds or removes beads for a single move.
+    ///
+    /// The number of beads never drops below zero.
+    ///
+    /// # Arguments
+    ///
+    /// * `coord` - The move whose beads are adjusted.
+    /// * `delta` - The number of beads to add, or to remove if negative.
+    pub fn adjust(&mut self, coord: Coord, delta: i32) {
+        let count = &mut self.beads[coord.index()];
+        *count = count.saturating_add_signed(delta);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+
+    use super::*;
+    use crate::{
+        BoardCodec,
+        SeededRng,
+    };
+
+    #[test]
+    fn test_initial_beads() {
+        let schedule: Vec<u32> = (0..9).map(initial_beads).collect();
+        assert_eq!(schedule, vec![4, 4, 3, 3, 2, 2, 1, 1, 1]);
+    }
+
+    #[test]
+    fn test_new_only_fills_legal_moves() {
+        let board = BoardCodec::new().decode("120000000").unwrap();
+        let matchbox = Matchbox::new(&board, 3);
+        assert_eq!(matchbox.beads(), [0, 0, 3, 3, 3, 3, 3, 3, 3]);
+        assert_eq!(matchbox.total(), 21);
+    }
+
+    #[test]
+    fn test_draw_only_returns_moves_with_beads() {
+        let mut rng = SeededRng::seed_from_u64(7);
+        let matchbox = Matchbox::from_beads([0, 0, 0, 0, 5, 0, 0, 0, 1]);
+        for _ in 0..100 {
+            let coord = matchbox.draw(&mut rng).unwrap();
+            assert!(coord.index() == 4 || coord.index() == 8);
+        }
+    }
+
+    #[test]
+    fn test_draw_from_empty_matchbox() {
+        let mut rng = SeededRng::seed_from_u64(7);
+        let matchbox = Matchbox::from_beads([0; 9]);
+        assert!(matchbox.is_empty());
+        assert_eq!(matchbox.draw(&mut rng), None);
+    }
+
+    #[test]
+    fn test_adjust_saturates_at_zero() {
+        let coord = Coord::from_index(0).unwrap();
+        let mut matchbox = Matchbox::from_beads([1, 0, 0, 0, 0, 0, 0, 0, 0]);
+        matchbox.adjust(coord, 3);
+        assert_eq!(matchbox.beads_for(coord), 4);
+        matchbox.adjust(coord, -10);
+        assert_eq!(matchbox.beads_for(coord), 0);
+    }
+}
@@ -0,0 +1,254 @@
+// SPDX-FileCopyrightText: 2022 - 2024 Ali Sajid Imami
+//
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+use crate::{
+    learning::unit_interval,
+    Coord,
+    GameResult,
+    Matchbox,
+};
+
+/// How many beads each drawn move gains or loses once a game is over.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum RewardSchedule {
+    /// Every drawn move gets the same number of beads, whatever point of the game it was
+    /// played at.
+    Fixed {
+        /// Beads added after a win.
+        win:  i32,
+        /// Beads added after a draw.
+        draw: i32,
+        /// Beads added after a loss; usually negative.
+        loss: i32,
+    },
+    /// The last move of the game gets the full reward, and each earlier move gets the reward of
+    /// the move after it multiplied by `factor`, rounded to the nearest bead.
+    Discounted {
+        /// Beads added to the last move after a win.
+        win:    i32,
+        /// Beads added to the last move after a draw.
+        draw:   i32,
+        /// Beads added to the last move after a loss; usually negative.
+        loss:   i32,
+        /// The discount applied for each move further from the end of the game, clamped like
+        /// [`Schedule::probability`](crate::Schedule::probability).
+        factor: f64,
+    },
+}
+
+impl RewardSchedule {
+    /// Returns the number of beads to add to a drawn move.
+    ///
+    /// # Arguments
+    ///
+    /// * `result` - The result of the game from MENACE's point of view.
+    /// * `moves_from_end` - How many of MENACE's moves were played after this one.
+    #[must_use]
+    #[allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap)]
+    pub fn reward(&self, result: GameResult, moves_from_end: usize) -> i32 {
+        let pick = |win: i32, draw: i32, loss: i32| match result {
+            GameResult::Win => win,
+            GameResult::Draw => draw,
+            GameResult::Loss => loss,
+        };
+
+        match *self {
+            Self::Fixed { win, draw, loss } => pick(win, draw, loss),
+            Self::Discounted {
+                win,
+                draw,
+                loss,
+                factor,
+            } => {
+                let factor = unit_interval(factor);
+                let exponent = i32::try_from(moves_from_end).unwrap_or(i32::MAX);
+                (f64::from(pick(win, draw, loss)) * factor.powi(exponent)).round() as i32
+            }
+        }
+    }
+}
+
+/// Decides how the beads in a matchbox change once a game is over.
+///
+/// The default policy is Michie's: 3 beads are added for a win, 1 for a draw, and 1 is taken away
+/// for a loss, with no limits on the number of beads.
+///
+/// # Examples
+///
+/// ```
+/// use tictacrustle::{
+///     GameResult,
+///     ReinforcementPolicy,
+///     RewardSchedule,
+/// };
+///
+/// let policy = ReinforcementPolicy {
+///     schedule:  RewardSchedule::Discounted {
+///         win:    4,
+///         draw:   2,
+///         loss:   -2,
+///         factor: 0.5,
+///     },
+///     min_beads: 1,
+///     max_beads: Some(20),
+/// };
+/// assert_eq!(policy.schedule.reward(GameResult::Win, 0), 4);
+/// assert_eq!(policy.schedule.reward(GameResult::Win, 1), 2);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ReinforcementPolicy {
+    /// How many beads each drawn move gains or loses.
+    pub schedule:  RewardSchedule,
+    /// The fewest beads a drawn move can be left with. A value above zero means MENACE never
+    /// rules a move out completely.
+    pub min_beads: u32,
+    /// The most beads a drawn move can hold, if limited.
+    pub max_beads: Option<u32>,
+}
+
+impl ReinforcementPolicy {
+    /// Returns Michie's original policy: +3 beads for a win, +1 for a draw and -1 for a loss.
+    #[must_use]
+    pub const fn classic() -> Self {
+        Self {
+            schedule:  RewardSchedule::Fixed {
+                win:  3,
+                draw: 1,
+                loss: -1,
+            },
+            min_beads: 0,
+            max_beads: None,
+        }
+    }
+
+    /// Applies the reward for one drawn move to its matchbox, keeping the number of beads for
+    /// the move within the policy's limits.
+    ///
+    /// # Arguments
+    ///
+    /// * `matchbox` - The matchbox the bead was drawn from.
+    /// * `bead` - The move the bead stands for.
+    /// * `result` - The result of the game from MENACE's point of view.
+    /// * `moves_from_end` - How many of MENACE's moves were played after this one.
+    pub fn apply(
+        &self,
+        matchbox: &mut Matchbox,
+        bead: Coord,
+        result: GameResult,
+        moves_from_end: usize,
+    ) {
+        let current = matchbox.beads_for(bead);
+        let max = self.max_beads.unwrap_or(u32::MAX).max(self.min_beads);
+        let target = current
+            .saturating_add_signed(self.schedule.reward(result, moves_from_end))
+            .clamp(self.min_beads, max);
+        matchbox.set_beads(bead, target);
+    }
+}
+
+impl Default for ReinforcementPolicy {
+    /// Creates the classic policy by calling [`ReinforcementPolicy::classic`].
+    fn default() -> Self {
+        Self::classic()
+    }
+}
+
+/// What MENACE does when the matchbox for a position has run out of beads.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EmptyBoxPolicy {
+    /// MENACE resigns the game, as Michie's original machine did.
+    #[default]
+    Resign,
+    /// The matchbox is refilled with its initial beads before drawing.
+    Refill,
+    /// A legal move is picked uniformly at random, and is reinforced like a drawn bead.
+    UniformRandom,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classic_rewards() {
+        let schedule = ReinforcementPolicy::classic().schedule;
+        for moves_from_end in 0..4 {
+            assert_eq!(schedule.reward(GameResult::Win, moves_from_end), 3);
+            assert_eq!(schedule.reward(GameResult::Draw, moves_from_end), 1);
+            assert_eq!(schedule.reward(GameResult::Loss, moves_from_end), -1);
+        }
+    }
+
+    #[test]
+    fn test_discounted_rewards() {
+        let schedule = RewardSchedule::Discounted {
+            win:    8,
+            draw:   4,
+            loss:   -8,
+            factor: 0.5,
+        };
+        let wins: Vec<i32> = (0..5)
+            .map(|moves_from_end| schedule.reward(GameResult::Win, moves_from_end))
+            .collect();
+        assert_eq!(wins, vec![8, 4, 2, 1, 1]);
+        assert_eq!(schedule.reward(GameResult::Loss, 2), -2);
+        assert_eq!(schedule.reward(GameResult::Draw, 3), 1);
+    }
+
+    #[test]
+    fn test_discount_factor_is_clamped() {
+        let rewards = |factor: f64| -> Vec<i32> {
+            let schedule = RewardSchedule::Discounted {
+                win: 8,
+                draw: 4,
+                loss: -8,
+                factor,
+            };
+            (0..3)
+                .map(|moves_from_end| schedule.reward(GameResult::Win, moves_from_end))
+                .collect()
+        };
+        assert_eq!(rewards(f64::NAN), vec![8, 0, 0]);
+        assert_eq!(rewards(-0.5), vec![8, 0, 0]);
+        assert_eq!(rewards(2.0), vec![8, 8, 8]);
+    }
+
+    #[test]
+    fn test_apply_respects_caps() {
+        let coord = Coord::from_index(0).unwrap();
+        let policy = ReinforcementPolicy {
+            min_beads: 1,
+            max_beads: Some(5),
+            ..ReinforcementPolicy::classic()
+        };
+
+        let mut matchbox = Matchbox::from_beads([4, 0, 0, 0, 0, 0, 0, 0, 0]);
+        policy.apply(&mut matchbox, coord, GameResult::Win, 0);
+        assert_eq!(matchbox.beads_for(coord), 5);
+
+        let mut matchbox = Matchbox::from_beads([1, 0, 0, 0, 0, 0, 0, 0, 0]);
+        policy.apply(&mut matchbox, coord, GameResult::Loss, 0);
+        assert_eq!(matchbox.beads_for(coord), 1);
+    }
+
+    #[test]
+    fn test_apply_without_caps() {
+        let coord = Coord::from_index(0).unwrap();
+        let policy = ReinforcementPolicy::classic();
+
+        let mut matchbox = Matchbox::from_beads([1, 0, 0, 0, 0, 0, 0, 0, 0]);
+        policy.apply(&mut matchbox, coord, GameResult::Loss, 0);
+        assert_eq!(matchbox.beads_for(coord), 0);
+        policy.apply(&mut matchbox, coord, GameResult::Loss, 0);
+        assert_eq!(matchbox.beads_for(coord), 0);
+    }
+}
@@ -0,0 +1,114 @@
+// SPDX-FileCopyrightText: 2022 - 2024 Ali Sajid Imami
+//
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use super::{
+    Matchbox,
+    Menace,
+};
+use crate::{
+    BoardCodec,
+    GameResult,
+    MatchboxStore,
+    PersistenceError,
+};
+
+impl Menace {
+    /// Loads MENACE from a store.
+    ///
+    /// A store that has never been saved to gives a new MENACE-C with no matchboxes.
+    ///
+    /// # Arguments
+    ///
+    /// * `store` - The store to load from.
+    ///
+    /// # Errors
+    ///
+    /// - [`PersistenceError::InvalidMatchbox`] if a matchbox key is not a reachable board, is a
+    ///   finished game, is not canonical for MENACE-C, or has beads for squares that are taken.
+    /// - Any other [`PersistenceError`] if the store cannot be read.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rand::SeedableRng;
+    /// use tictacrustle::{
+    ///     Board,
+    ///     GameResult,
+    ///     MemoryStore,
+    ///     Menace,
+    ///     SeededRng,
+    /// };
+    ///
+    /// let mut store = MemoryStore::new();
+    /// let mut rng = SeededRng::seed_from_u64(1);
+    /// let mut menace = Menace::new();
+    ///
+    /// menace.choose_move(&Board::new(), &mut rng).unwrap();
+    /// menace.reinforce_into(GameResult::Win, &mut store).unwrap();
+    ///
+    /// assert_eq!(Menace::from_store(&store).unwrap(), menace);
+    /// ```
+    pub fn from_store(store: &dyn MatchboxStore) -> Result<Self, PersistenceError> {
+        let mut menace = Self::with_config(store.config()?.unwrap_or_default());
+        menace.games_played = store.games_played()?;
+
+        let codec = BoardCodec::new();
+        for (key, matchbox) in store.matchboxes()? {
+            let valid = codec
+                .from_number(key)
+                .is_ok_and(|board| menace.is_valid_matchbox(&board, &matchbox));
+            if !valid {
+                return Err(PersistenceError::InvalidMatchbox(key.to_string()));
+            }
+            menace.boxes.insert(key, matchbox);
+        }
+        Ok(menace)
+    }
+
+    /// Replaces everything in a store with this MENACE.
+    ///
+    /// # Arguments
+    ///
+    /// * `store` - The store to write to.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`PersistenceError`] if the store cannot be written.
+    pub fn save_to(&self, store: &mut dyn MatchboxStore) -> Result<(), PersistenceError> {
+        store.save(self)
+    }
+
+    /// Applies reinforcement like [`Menace::reinforce`], then commits the game and the
+    /// matchboxes it drew from to a store.
+    ///
+    /// MENACE itself is reinforced even if the store cannot be written, so the store can be
+    /// brought up to date later with [`Menace::save_to`].
+    ///
+    /// # Arguments
+    ///
+    /// * `result` - The result of the game from MENACE's point of view.
+    /// * `store` - The store to commit to.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`PersistenceError`] if the store cannot be written.
+    pub fn reinforce_into(
+        &mut self,
+        result: GameResult,
+        store: &mut dyn MatchboxStore,
+    ) -> Result<(), PersistenceError> {
+        let mut keys: Vec<u16> = self.history.iter().map(|draw| draw.matchbox).collect();
+        keys.sort_unstable();
+        keys.dedup();
+
+        self.reinforce(result);
+
+        let changed: Vec<(u16, Matchbox)> = keys
+            .into_iter()
+            .filter_map(|key| self.boxes.get(&key).map(|matchbox| (key, *matchbox)))
+            .collect();
+        store.commit_game(self.games_played, &changed)
+    }
+}
@@ -0,0 +1,412 @@
+// SPDX-FileCopyrightText: 2022 - 2024 Ali Sajid Imami
+//
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use std::{
+    fmt,
+    sync::Arc,
+};
+
+use rand::{
+    seq::IndexedRandom,
+    Rng,
+    SeedableRng,
+};
+
+use crate::{
+    learning::unit_interval,
+    Agent,
+    Board,
+    Coord,
+    RandomAgent,
+    Seeded,
+    SeededRng,
+    Solver,
+    Symbol,
+};
+
+/// The error rate of the [`Difficulty::Hard`] opponent.
+const HARD_EPSILON: f64 = 0.25;
+
+/// The strength of a ready-made computer opponent.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum Difficulty {
+    /// Plays a uniformly random legal move. See [`RandomAgent`].
+    Easy,
+    /// Wins when it can and blocks when it must. See [`HeuristicAgent`].
+    #[default]
+    Medium,
+    /// Plays perfectly, except for the occasional random blunder. See [`NoisyPerfectAgent`].
+    Hard,
+    /// Never loses. See [`PerfectAgent`].
+    Perfect,
+}
+
+impl Difficulty {
+    /// All difficulties, from weakest to strongest.
+    pub const ALL: [Self; 4] = [Self::Easy, Self::Medium, Self::Hard, Self::Perfect];
+
+    /// Creates an opponent of this difficulty.
+    ///
+    /// The harder opponents solve the whole game when they are created, which takes a moment.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tictacrustle::{
+    ///     play_match,
+    ///     Difficulty,
+    /// };
+    ///
+    /// let mut first = Difficulty::Perfect.agent();
+    /// let mut second = Difficulty::Perfect.agent();
+    ///
+    /// let record = play_match(first.as_mut(), second.as_mut()).unwrap();
+    /// assert_eq!(record.outcome.winner(), None);
+    /// ```
+    #[must_use]
+    pub fn agent(self) -> Box<dyn Agent + Send> {
+        self.agent_with_rng(SeededRng::from_os_rng())
+    }
+
+    /// Creates an opponent of this difficulty that draws from the given random number
+    /// generator, so its games are reproducible.
+    ///
+    /// # Arguments
+    ///
+    /// * `rng` - The random number generator the opponent uses.
+    #[must_use]
+    pub fn agent_with_rng(self, rng: SeededRng) -> Box<dyn Agent + Send> {
+        match self {
+            Self::Easy => Box::new(RandomAgent::new().with_rng(rng)),
+            Self::Medium => Box::new(HeuristicAgent::new().with_rng(rng)),
+            Self::Hard => Box::new(NoisyPerfectAgent::with_perfect(
+                PerfectAgent::new().with_rng(rng),
+                HARD_EPSILON,
+            )),
+            Self::Perfect => Box::new(PerfectAgent::new().with_rng(rng)),
+        }
+    }
+}
+
+impl fmt::Display for Difficulty {
+    /// Formats the difficulty as a lowercase word, such as `medium`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Easy => write!(f, "easy"),
+            Self::Medium => write!(f, "medium"),
+            Self::Hard => write!(f, "hard"),
+            Self::Perfect => write!(f, "perfect"),
+        }
+    }
+}
+
+/// An agent that wins if it can and blocks if it must.
+///
+/// Otherwise it takes the centre if it is free, then a random corner, then a random edge. It
+/// cannot see forks coming, so it can be beaten.
+#[derive(Debug, Clone)]
+pub struct HeuristicAgent {
+    /// The random number generator used to break ties.
+    rng: SeededRng,
+}
+
+impl HeuristicAgent {
+    /// Creates a new `HeuristicAgent` seeded from the operating system.
+    ///
+    /// Use [`Seeded::with_seed`] to make its moves reproducible.
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            rng: SeededRng::from_os_rng(),
+        }
+    }
+}
+
+impl Seeded for HeuristicAgent {
+    fn rng_mut(&mut self) -> &mut SeededRng {
+        &mut self.rng
+    }
+}
+
+impl Default for HeuristicAgent {
+    /// Creates a default heuristic agent by calling [`HeuristicAgent::new`].
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Agent for HeuristicAgent {
+    fn choose_move(&mut self, board: &Board, symbol: Symbol) -> Option<Coord> {
+        heuristic_move(board, symbol, &mut self.rng)
+    }
+}
+
+/// Chooses a move by the win, block, centre, corner, edge rule of [`HeuristicAgent`].
+///
+/// # Returns
+///
+/// The chosen square, or `None` if the game is over.
+pub(crate) fn heuristic_move<R: Rng + ?Sized>(
+    board: &Board,
+    symbol: Symbol,
+    rng: &mut R,
+) -> Option<Coord> {
+    let legal = board.legal_moves();
+    let is_corner = |coord: &Coord| coord.row() != 2 && coord.col() != 2;
+    let is_centre = |coord: &Coord| coord.row() == 2 && coord.col() == 2;
+
+    let candidates = [
+        board.winning_moves(symbol),
+        board.winning_moves(symbol.opponent()),
+        legal.iter().copied().filter(is_centre).collect(),
+        legal.iter().copied().filter(is_corner).collect(),
+        legal,
+    ];
+    candidates
+        .iter()
+        .find(|moves| !moves.is_empty())
+        .and_then(|moves| moves.choose(rng).copied())
+}
+
+/// An agent that always plays a move achieving the game-theoretic value of the position.
+///
+/// When several moves are equally good, one of them is picked at random, so games against it
+/// are not all alike.
+#[derive(Debug, Clone)]
+pub struct PerfectAgent {
+    /// The oracle holding the value of every position.
+    solver: Arc<Solver>,
+    /// The random number generator used to break ties.
+    rng:    SeededRng,
+}
+
+impl PerfectAgent {
+    /// Creates a new `PerfectAgent` with its own [`Solver`], seeded from the operating system.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::with_solver(Arc::new(Solver::new()))
+    }
+
+    /// Creates a new `PerfectAgent` that shares an existing [`Solver`], seeded from the
+    /// operating system.
+    ///
+    /// # Arguments
+    ///
+    /// * `solver` - The oracle holding the value of every position.
+    #[must_use]
+    pub fn with_solver(solver: Arc<Solver>) -> Self {
+        Self {
+            solver,
+            rng: SeededRng::from_os_rng(),
+        }
+    }
+
+    /// Returns the solver the agent plays from.
+    #[must_use]
+    pub const fn solver(&self) -> &Arc<Solver> {
+        &self.solver
+    }
+}
+
+impl Default for PerfectAgent {
+    /// Creates a default perfect agent by calling [`PerfectAgent::new`].
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Seeded for PerfectAgent {
+    fn rng_mut(&mut self) -> &mut SeededRng {
+        &mut self.rng
+    }
+}
+
+impl Agent for PerfectAgent {
+    fn choose_move(&mut self, board: &Board, _symbol: Symbol) -> Option<Coord> {
+        self.solver.best_moves(board).choose(&mut self.rng).copied()
+    }
+}
+
+/// A perfect player that blunders on purpose.
+///
+/// On each move it plays a uniformly random legal move with probability `epsilon`, and a perfect
+/// move otherwise. An `epsilon` of zero gives a [`PerfectAgent`], and an `epsilon` of one gives a
+/// [`RandomAgent`].
+#[derive(Debug, Clone)]
+pub struct NoisyPerfectAgent {
+    /// The perfect player consulted when the agent does not blunder.
+    perfect: PerfectAgent,
+    /// The probability of playing a random move instead of a perfect one.
+    epsilon: f64,
+}
+
+impl NoisyPerfectAgent {
+    /// Creates a new `NoisyPerfectAgent` with its own [`Solver`], seeded from the operating
+    /// system.
+    ///
+    /// # Arguments
+    ///
+    /// * `epsilon` - The probability of playing a random move, clamped like
+    ///   [`Schedule::probability`](crate::Schedule::probability).
+    #[must_use]
+    pub fn new(epsilon: f64) -> Self {
+        Self::with_perfect(PerfectAgent::new(), epsilon)
+    }
+
+    /// Creates a new `NoisyPerfectAgent` from an existing perfect player.
+    ///
+    /// # Arguments
+    ///
+    /// * `perfect` - The perfect player consulted when the agent does not blunder.
+    /// * `epsilon` - The probability of playing a random move, clamped like
+    ///   [`Schedule::probability`](crate::Schedule::probability).
+    #[must_use]
+    pub fn with_perfect(perfect: PerfectAgent, epsilon: f64) -> Self {
+        Self {
+            perfect,
+            epsilon: unit_interval(epsilon),
+        }
+    }
+
+    /// Returns the probability of playing a random move.
+    #[must_use]
+    pub const fn epsilon(&self) -> f64 {
+        self.epsilon
+    }
+}
+
+impl Seeded for NoisyPerfectAgent {
+    fn rng_mut(&mut self) -> &mut SeededRng {
+        &mut self.perfect.rng
+    }
+}
+
+impl Agent for NoisyPerfectAgent {
+    fn choose_move(&mut self, board: &Board, symbol: Symbol) -> Option<Coord> {
+        if self.perfect.rng.random_bool(self.epsilon) {
+            board.legal_moves().choose(&mut self.perfect.rng).copied()
+        } else {
+            self.perfect.choose_move(board, symbol)
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use rstest::rstest;
+
+    use super::*;
+    use crate::{
+        play_match,
+        BoardCodec,
+        GameResult,
+        MatchOutcome,
+        ScriptedAgent,
+    };
+
+    #[rstest]
+    #[case("110220000", vec![(1, 3)])]
+    #[case("100220100", vec![(2, 3)])]
+    #[case("000010000", vec![(1, 1), (1, 3), (3, 1), (3, 3)])]
+    fn test_heuristic_agent(#[case] encoded: &str, #[case] expected: Vec<(usize, usize)>) {
+        let board = BoardCodec::new().decode(encoded).unwrap();
+        let expected: Vec<Coord> = expected
+            .into_iter()
+            .map(|(row, col)| Coord::new(row, col).unwrap())
+            .collect();
+
+        let mut agent = HeuristicAgent::new().with_seed(1);
+        let choice = agent.choose_move(&board, board.to_move()).unwrap();
+        assert!(expected.contains(&choice));
+    }
+
+    #[test]
+    fn test_heuristic_agent_takes_centre() {
+        let mut agent = HeuristicAgent::new().with_seed(1);
+        let choice = agent.choose_move(&Board::new(), Symbol::X);
+        assert_eq!(choice, Coord::new(2, 2).ok());
+    }
+
+    #[test]
+    fn test_perfect_agent_never_loses() {
+        let solver = Arc::new(Solver::new());
+        let mut perfect =
+            PerfectAgent::with_solver(Arc::clone(&solver)).with_rng(SeededRng::seed_from_u64(1));
+        let mut opponent = RandomAgent::new().with_seed(2);
+
+        for _ in 0..50 {
+            let record = play_match(&mut perfect, &mut opponent).unwrap();
+            assert_ne!(record.outcome.result_for(Symbol::X), GameResult::Loss);
+            let record = play_match(&mut opponent, &mut perfect).unwrap();
+            assert_ne!(record.outcome.result_for(Symbol::O), GameResult::Loss);
+        }
+    }
+
+    #[test]
+    fn test_perfect_agent_punishes_blunders() {
+        let mut perfect = PerfectAgent::with_solver(Arc::new(Solver::new()))
+            .with_rng(SeededRng::seed_from_u64(3));
+        for _ in 0..10 {
+            // Answering in the edges, rather than the centre or a corner, loses.
+            let mut blunderer = ScriptedAgent::new(vec![
+                Coord::new(1, 2).unwrap(),
+                Coord::new(2, 1).unwrap(),
+                Coord::new(2, 3).unwrap(),
+                Coord::new(3, 2).unwrap(),
+            ]);
+            let record = play_match(&mut perfect, &mut blunderer).unwrap();
+            assert_eq!(record.outcome.result_for(Symbol::X), GameResult::Win);
+        }
+    }
+
+    #[rstest]
+    #[case(-1.0, 0.0)]
+    #[case(0.3, 0.3)]
+    #[case(2.0, 1.0)]
+    #[case(f64::NAN, 0.0)]
+    #[case(f64::INFINITY, 1.0)]
+    fn test_noisy_epsilon_is_clamped(#[case] epsilon: f64, #[case] expected: f64) {
+        let solver = Arc::new(Solver::new());
+        let agent = NoisyPerfectAgent::with_perfect(PerfectAgent::with_solver(solver), epsilon);
+        assert!((agent.epsilon() - expected).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn test_noiseless_agent_is_perfect() {
+        let solver = Arc::new(Solver::new());
+        let mut agent = NoisyPerfectAgent::with_perfect(
+            PerfectAgent::with_solver(Arc::clone(&solver)).with_rng(SeededRng::seed_from_u64(4)),
+            0.0,
+        );
+        let mut opponent = PerfectAgent::with_solver(solver).with_rng(SeededRng::seed_from_u64(5));
+
+        for _ in 0..20 {
+            let record = play_match(&mut agent, &mut opponent).unwrap();
+            assert!(matches!(record.outcome, MatchOutcome::Finished(_)));
+            assert_eq!(record.outcome.winner(), None);
+        }
+    }
+
+    #[test]
+    fn test_seeded_difficulties_are_reproducible() {
+        for difficulty in [Difficulty::Easy, Difficulty::Medium] {
+            let play = || {
+                let mut first = difficulty.agent_with_rng(SeededRng::seed_from_u64(1));
+                let mut second = Difficulty::Easy.agent_with_rng(SeededRng::seed_from_u64(2));
+                (0..10)
+                    .map(|_| play_match(first.as_mut(), second.as_mut()).unwrap().moves)
+                    .collect::<Vec<_>>()
+            };
+            assert_eq!(play(), play());
+        }
+    }
+
+    #[test]
+    fn test_difficulty_display() {
+        let names: Vec<String> = Difficulty::ALL.iter().map(ToString::to_string).collect();
+        assert_eq!(names, ["easy", "medium", "hard", "perfect"]);
+    }
+}
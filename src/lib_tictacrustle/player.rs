@@ -3,7 +3,17 @@
 // SPDX-License-Identifier: Apache-2.0
 // SPDX-License-Identifier: MIT
 
-use std::fmt;
+use std::{
+    fmt,
+    str::FromStr,
+};
+
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+use crate::GameError;
 
 /// Represents a player in the game of Tic Tac Toe.
 ///
@@ -28,11 +38,17 @@ impl Player {
     ///
     /// # Panics
     ///
-    /// Panics if the provided symbol is not `"X"` or `"O"`.
+    /// Panics if the provided symbol is not `"X"` or `"O"`. Parse a [`Symbol`] with
+    /// [`str::parse`] and convert it with [`Player::from`] to handle invalid input instead.
     ///
     /// # Examples
     ///
     /// ```
+    /// use tictacrustle::{
+    ///     Player,
+    ///     Symbol,
+    /// };
+    ///
     /// let player_x = Player::new("X");
     /// assert_eq!(player_x.symbol, Symbol::X);
     /// ```
@@ -46,12 +62,21 @@ impl Player {
     }
 }
 
+impl From<Symbol> for Player {
+    /// Creates a `Player` using the given symbol.
+    fn from(symbol: Symbol) -> Self {
+        Self { symbol }
+    }
+}
+
 impl fmt::Display for Player {
     /// Formats the player as a string for display.
     ///
     /// # Examples
     ///
     /// ```
+    /// use tictacrustle::Player;
+    ///
     /// let player = Player::new("X");
     /// println!("{}", player); // Outputs: Player X
     /// ```
@@ -63,7 +88,7 @@ impl fmt::Display for Player {
 /// Represents a Tic Tac Toe symbol used by a player.
 ///
 /// The symbol is either `X` or `O`, and is used to mark moves on the board.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum Symbol {
     /// The `X` symbol, typically used by the first player.
     X,
@@ -71,12 +96,60 @@ pub enum Symbol {
     O,
 }
 
+impl Symbol {
+    /// Returns the symbol of the opposing player.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tictacrustle::Symbol;
+    ///
+    /// assert_eq!(Symbol::X.opponent(), Symbol::O);
+    /// assert_eq!(Symbol::O.opponent(), Symbol::X);
+    /// ```
+    #[must_use]
+    pub const fn opponent(self) -> Self {
+        match self {
+            Self::X => Self::O,
+            Self::O => Self::X,
+        }
+    }
+}
+
+impl FromStr for Symbol {
+    type Err = GameError;
+
+    /// Parses a symbol from `"X"` or `"O"`, ignoring case.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`GameError::InvalidSymbol`] for any other string.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tictacrustle::Symbol;
+    ///
+    /// assert_eq!("o".parse::<Symbol>(), Ok(Symbol::O));
+    /// assert!("Z".parse::<Symbol>().is_err());
+    /// ```
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "X" | "x" => Ok(Self::X),
+            "O" | "o" => Ok(Self::O),
+            _ => Err(GameError::InvalidSymbol),
+        }
+    }
+}
+
 impl fmt::Display for Symbol {
     /// Formats the symbol as either "X" or "O".
     ///
     /// # Examples
     ///
     /// ```
+    /// use tictacrustle::Symbol;
+    ///
     /// let sym = Symbol::X;
     /// println!("{}", sym); // Outputs: X
     /// ```
@@ -0,0 +1,337 @@
+// SPDX-FileCopyrightText: 2022 - 2024 Ali Sajid Imami
+//
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+use crate::{
+    Board,
+    BoardCodec,
+    Coord,
+    GameError,
+    GameResult,
+    Symbol,
+};
+
+/// Where a game played over the network stands.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GameState {
+    /// The game is waiting in the lobby for a second player.
+    Waiting,
+    /// The game is still being played.
+    InProgress,
+    /// A player completed a line.
+    Won,
+    /// The board is full and nobody won.
+    Draw,
+    /// A player resigned.
+    Resigned,
+}
+
+impl GameState {
+    /// Checks whether the game is over.
+    #[must_use]
+    pub const fn is_over(self) -> bool {
+        !matches!(self, Self::Waiting | Self::InProgress)
+    }
+}
+
+/// The body of a request to start a game against MENACE.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NewGameRequest {
+    /// Whether MENACE plays `X` and moves first. By default the human does.
+    #[serde(default)]
+    pub menace_first: bool,
+}
+
+/// A game as reported by the server.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GameView {
+    /// The identifier of the game.
+    pub id:      String,
+    /// The board, encoded by [`BoardCodec::encode`].
+    pub board:   String,
+    /// The symbol the human plays.
+    pub human:   Symbol,
+    /// The symbol MENACE plays.
+    pub menace:  Symbol,
+    /// Where the game stands.
+    pub state:   GameState,
+    /// The player to move, if the game is still being played.
+    pub to_move: Option<Symbol>,
+    /// The winner, if any.
+    pub winner:  Option<Symbol>,
+    /// The moves played, in order, starting with `X`.
+    pub moves:   Vec<Coord>,
+}
+
+impl GameView {
+    /// Decodes the board of the game.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`GameError::InvalidEncoding`] if the server sent a malformed board.
+    pub fn decode_board(&self) -> Result<Board, GameError> {
+        BoardCodec::new().decode(&self.board)
+    }
+}
+
+/// The body of a request to open a game in the lobby, or to join one.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct JoinRequest {
+    /// The name shown to the other player. The server picks one if it is missing.
+    #[serde(default)]
+    pub name: Option<String>,
+}
+
+/// A game in the lobby waiting for a second player.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LobbyEntry {
+    /// The identifier of the game.
+    pub id:           String,
+    /// The name of the player who opened the game.
+    pub host:         String,
+    /// The seconds since the game was opened.
+    pub waiting_secs: u64,
+}
+
+/// A game between two humans as reported by the server.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MatchView {
+    /// The identifier of the game.
+    pub id:      String,
+    /// The board, encoded by [`BoardCodec::encode`].
+    pub board:   String,
+    /// The name of the player playing `X`, who opened the game.
+    pub x:       String,
+    /// The name of the player playing `O`, once someone has joined.
+    pub o:       Option<String>,
+    /// Where the game stands.
+    pub state:   GameState,
+    /// The player to move, if the game is being played.
+    pub to_move: Option<Symbol>,
+    /// The winner, if any.
+    pub winner:  Option<Symbol>,
+    /// The moves played, in order, starting with `X`.
+    pub moves:   Vec<Coord>,
+}
+
+impl MatchView {
+    /// Decodes the board of the game.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`GameError::InvalidEncoding`] if the server sent a malformed board.
+    pub fn decode_board(&self) -> Result<Board, GameError> {
+        BoardCodec::new().decode(&self.board)
+    }
+}
+
+/// The seat a player takes by opening or joining a game in the lobby.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PlayerTicket {
+    /// The identifier of the game.
+    pub id:     String,
+    /// The secret that authorizes the player's moves.
+    pub token:  String,
+    /// The symbol the player plays.
+    pub symbol: Symbol,
+    /// The game after the player took their seat.
+    pub game:   MatchView,
+}
+
+/// The body of a move in a game between two humans.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MatchMove {
+    /// The token of the player moving, from their [`PlayerTicket`].
+    pub token: String,
+    /// The square played.
+    pub coord: Coord,
+}
+
+/// The server's answer to a move.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MoveResponse {
+    /// The move the human played.
+    pub human_move:  Coord,
+    /// MENACE's reply, or `None` if the game ended or MENACE resigned.
+    pub menace_move: Option<Coord>,
+    /// The game after both moves.
+    pub game:        GameView,
+}
+
+/// The final outcome of a game.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OutcomeView {
+    /// The identifier of the game.
+    pub id:     String,
+    /// How the game ended.
+    pub state:  GameState,
+    /// The winner, if any.
+    pub winner: Option<Symbol>,
+    /// The result from the human's point of view.
+    pub result: GameResult,
+}
+
+/// A move in the log of a game, as reported by the server.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MoveRecord {
+    /// The symbol of the player who moved.
+    pub player:     Symbol,
+    /// The square played.
+    pub coord:      Coord,
+    /// The milliseconds between the start of the game and the move.
+    pub elapsed_ms: u64,
+}
+
+/// A game session as listed for administrators.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SessionSummary {
+    /// The identifier of the game.
+    pub id:         String,
+    /// The symbol the human plays.
+    pub human:      Symbol,
+    /// Where the game stands.
+    pub state:      GameState,
+    /// The number of moves played.
+    pub moves:      usize,
+    /// The seconds since the game started.
+    pub age_secs:   u64,
+    /// The seconds since the game was last played or viewed.
+    pub idle_secs:  u64,
+    /// The number of clients watching the game over a WebSocket.
+    pub spectators: usize,
+}
+
+/// A message pushed to the clients watching a game over a WebSocket.
+///
+/// Every message is a JSON object whose `type` field names the variant, such as
+/// `{"type": "turn", "id": "…", "to_move": "O"}`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum GameEvent {
+    /// The game as it stands, sent when a client subscribes and after every move.
+    Board {
+        /// The game, including its board and the moves played.
+        game:      GameView,
+        /// The move that changed the board, or `None` for the first message.
+        last_move: Option<MoveRecord>,
+    },
+    /// A game between two humans as it stands, sent when a client subscribes, when the second
+    /// player joins, and after every move.
+    Match {
+        /// The game, including its board, its players and the moves played.
+        game:      MatchView,
+        /// The move that changed the board, or `None` if the board did not change.
+        last_move: Option<MoveRecord>,
+    },
+    /// The player to move has changed.
+    Turn {
+        /// The identifier of the game.
+        id:      String,
+        /// The player to move.
+        to_move: Symbol,
+    },
+    /// The game is over.
+    Outcome {
+        /// How the game ended, from the human's point of view.
+        outcome: OutcomeView,
+    },
+    /// A game between two humans is over.
+    Finished {
+        /// The identifier of the game.
+        id:     String,
+        /// How the game ended.
+        state:  GameState,
+        /// The winner, if any.
+        winner: Option<Symbol>,
+    },
+    /// The server dropped the game, so no more messages will follow.
+    Expired {
+        /// The identifier of the game.
+        id: String,
+    },
+}
+
+/// The body of every error response.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ErrorResponse {
+    /// A description of what went wrong.
+    pub error: String,
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_game_view_json() {
+        let view = GameView {
+            id:      "1".to_string(),
+            board:   "100020000".to_string(),
+            human:   Symbol::X,
+            menace:  Symbol::O,
+            state:   GameState::InProgress,
+            to_move: Some(Symbol::X),
+            winner:  None,
+            moves:   vec![Coord::new(1, 1).unwrap(), Coord::new(2, 2).unwrap()],
+        };
+        let json = serde_json::to_value(&view).unwrap();
+        assert_eq!(json["state"], "in_progress");
+        assert_eq!(json["to_move"], "X");
+        assert_eq!(json["moves"][1]["row"], 2);
+
+        let parsed: GameView = serde_json::from_value(json).unwrap();
+        assert_eq!(parsed, view);
+        assert!(parsed
+            .decode_board()
+            .unwrap()
+            .square(Coord::new(2, 2).unwrap())
+            .is_o());
+    }
+
+    #[test]
+    fn test_new_game_request_defaults() {
+        let request: NewGameRequest = serde_json::from_str("{}").unwrap();
+        assert!(!request.menace_first);
+    }
+
+    #[test]
+    fn test_game_event_json() {
+        let event = GameEvent::Turn {
+            id:      "a1".to_string(),
+            to_move: Symbol::O,
+        };
+        let json = serde_json::to_value(&event).unwrap();
+        assert_eq!(json["type"], "turn");
+        assert_eq!(json["to_move"], "O");
+        assert_eq!(serde_json::from_value::<GameEvent>(json).unwrap(), event);
+
+        let expired: GameEvent =
+            serde_json::from_str(r#"{"type": "expired", "id": "a1"}"#).unwrap();
+        assert_eq!(
+            expired,
+            GameEvent::Expired {
+                id: "a1".to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn test_match_move_json() {
+        let parsed: MatchMove =
+            serde_json::from_str(r#"{"token": "abc", "coord": {"row": 3, "col": 1}}"#).unwrap();
+        assert_eq!(parsed.token, "abc");
+        assert_eq!(parsed.coord, Coord::new(3, 1).unwrap());
+
+        let request: JoinRequest = serde_json::from_str("{}").unwrap();
+        assert_eq!(request.name, None);
+        assert!(!GameState::Waiting.is_over());
+    }
+}
@@ -0,0 +1,552 @@
+// SPDX-FileCopyrightText: 2022 - 2024 Ali Sajid Imami
+//
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use std::collections::HashMap;
+
+use crate::{
+    Agent,
+    Board,
+    BoardCodec,
+    Coord,
+    GameStatus,
+    Line,
+    SquareValue,
+    Symbol,
+};
+
+/// The score of a won position, before it is reduced by the number of moves needed to win.
+pub const WIN_SCORE: i32 = 1_000;
+
+/// Scores further than this from zero are wins or losses rather than heuristic estimates.
+const WIN_THRESHOLD: i32 = WIN_SCORE - 100;
+
+/// Estimates the value of a position that the search does not look beyond.
+///
+/// Closures of the form `Fn(&Board, Symbol) -> i32` implement this trait, so a custom heuristic
+/// can be plugged into a [`Search`] without declaring a type.
+pub trait Evaluator {
+    /// Returns an estimate of the position for the given player, where higher is better.
+    ///
+    /// Estimates must stay well within `±WIN_SCORE` so they are never mistaken for a forced
+    /// result.
+    ///
+    /// # Arguments
+    ///
+    /// * `board` - The position to evaluate. The game is still in progress.
+    /// * `symbol` - The player the estimate is for, which is the player to move.
+    fn evaluate(&self, board: &Board, symbol: Symbol) -> i32;
+}
+
+impl<F> Evaluator for F
+where
+    F: Fn(&Board, Symbol) -> i32,
+{
+    fn evaluate(&self, board: &Board, symbol: Symbol) -> i32 {
+        self(board, symbol)
+    }
+}
+
+/// The default heuristic: every line a player could still complete counts for them, and lines
+/// holding two of their marks count ten times as much as lines holding one.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LineEvaluator;
+
+impl Evaluator for LineEvaluator {
+    fn evaluate(&self, board: &Board, symbol: Symbol) -> i32 {
+        let own = SquareValue::from(symbol);
+        let other = SquareValue::from(symbol.opponent());
+
+        Line::ALL
+            .into_iter()
+            .map(|line| {
+                let values = board.get_line(line).map(|square| square.get_value());
+                let own_count = values.iter().filter(|&&value| value == own).count();
+                let other_count = values.iter().filter(|&&value| value == other).count();
+                let weight = |count: usize| match count {
+                    1 => 1,
+                    2 => 10,
+                    _ => 0,
+                };
+                match (own_count, other_count) {
+                    (count, 0) => weight(count),
+                    (0, count) => -weight(count),
+                    _ => 0,
+                }
+            })
+            .sum()
+    }
+}
+
+/// The settings of a [`Search`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SearchConfig {
+    /// The number of moves to look ahead before falling back to the evaluator, or `None` to
+    /// search until every game is over.
+    pub max_depth:           Option<usize>,
+    /// Whether positions are cached in a transposition table keyed by their canonical form.
+    pub transposition_table: bool,
+    /// Whether moves are searched in a promising order (the cached best move, then the centre,
+    /// then corners, then edges) so that more of the tree can be pruned.
+    pub move_ordering:       bool,
+}
+
+impl Default for SearchConfig {
+    /// Creates a configuration for a full-depth search with every optimisation enabled.
+    fn default() -> Self {
+        Self {
+            max_depth:           None,
+            transposition_table: true,
+            move_ordering:       true,
+        }
+    }
+}
+
+/// The result of searching a position.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SearchResult {
+    /// The best move found, or `None` if the game is already over.
+    pub best_move:           Option<Coord>,
+    /// The score of the position for the player to move. Scores above [`WIN_SCORE`] minus 100
+    /// are forced wins, and their distance from `WIN_SCORE` is the number of moves to the win.
+    pub score:               i32,
+    /// The number of positions visited during the search.
+    pub nodes:               u64,
+    /// The sequence of moves both players are expected to play, starting with `best_move`.
+    pub principal_variation: Vec<Coord>,
+}
+
+/// Which side of the true score a cached score lies on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Bound {
+    /// The cached score is the true score.
+    Exact,
+    /// The true score is at least the cached score.
+    Lower,
+    /// The true score is at most the cached score.
+    Upper,
+}
+
+/// A cached search result for one canonical position.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct TableEntry {
+    /// The remaining depth the position was searched to.
+    depth:     usize,
+    /// The score of the position, relative to the position itself rather than the root.
+    score:     i32,
+    /// How the score relates to the true score.
+    bound:     Bound,
+    /// The best move found, on the canonical board.
+    best_move: Coord,
+}
+
+/// A negamax search with alpha-beta pruning.
+///
+/// The search can be limited in depth, in which case positions at the horizon are scored by a
+/// pluggable [`Evaluator`]. Results are cached in a transposition table keyed by the
+/// [`Board::canonical`] form of each position, which persists across searches until
+/// [`Search::clear`] is called.
+///
+/// # Examples
+///
+/// ```
+/// use tictacrustle::{
+///     Board,
+///     Search,
+///     SearchConfig,
+/// };
+///
+/// let mut search = Search::new(SearchConfig::default());
+/// let result = search.search(&Board::new());
+/// assert_eq!(result.score, 0);
+/// assert_eq!(result.principal_variation.len(), 9);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Search<E = LineEvaluator> {
+    /// The settings of the search.
+    config:    SearchConfig,
+    /// The heuristic used at the search horizon.
+    evaluator: E,
+    /// Cached results, keyed by the base-3 number of the canonical board.
+    table:     HashMap<u16, TableEntry>,
+    /// The number of positions visited during the current search.
+    nodes:     u64,
+}
+
+impl Search {
+    /// Creates a new `Search` using the default [`LineEvaluator`].
+    #[must_use]
+    pub fn new(config: SearchConfig) -> Self {
+        Self::with_evaluator(config, LineEvaluator)
+    }
+}
+
+impl<E: Evaluator> Search<E> {
+    /// Creates a new `Search` with a custom evaluator.
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - The settings of the search.
+    /// * `evaluator` - The heuristic used at the search horizon.
+    pub fn with_evaluator(config: SearchConfig, evaluator: E) -> Self {
+        Self {
+            config,
+            evaluator,
+            table: HashMap::new(),
+            nodes: 0,
+        }
+    }
+
+    /// Returns the settings of the search.
+    pub const fn config(&self) -> &SearchConfig {
+        &self.config
+    }
+
+    /// Empties the transposition table.
+    pub fn clear(&mut self) {
+        self.table.clear();
+    }
+
+    /// Searches a position for the best move of the player to move.
+    ///
+    /// # Arguments
+    ///
+    /// * `board` - The position to search.
+    pub fn search(&mut self, board: &Board) -> SearchResult {
+        self.nodes = 0;
+        let depth = self
+            .config
+            .max_depth
+            .unwrap_or(usize::MAX)
+            .min(board.count(SquareValue::Empty));
+
+        let mut principal_variation = Vec::new();
+        let score = self.negamax(
+            board,
+            depth,
+            0,
+            -WIN_SCORE - 1,
+            WIN_SCORE + 1,
+            &mut principal_variation,
+        );
+
+        SearchResult {
+            best_move: principal_variation.first().copied(),
+            score,
+            nodes: self.nodes,
+            principal_variation,
+        }
+    }
+
+    /// Scores a position for the player to move, filling `line` with the principal variation.
+    fn negamax(
+        &mut self,
+        board: &Board,
+        depth: usize,
+        ply: i32,
+        mut alpha: i32,
+        mut beta: i32,
+        line: &mut Vec<Coord>,
+    ) -> i32 {
+        self.nodes += 1;
+        line.clear();
+
+        match board.status() {
+            GameStatus::Won { .. } => return ply - WIN_SCORE,
+            GameStatus::Draw => return 0,
+            GameStatus::InProgress { to_move } if depth == 0 => {
+                return self.evaluator.evaluate(board, to_move);
+            }
+            GameStatus::InProgress { .. } => {}
+        }
+
+        let (canonical, transform) = board.canonical();
+        let key = BoardCodec::new().to_number(&canonical);
+        let mut cached_move = None;
+        if self.config.transposition_table {
+            if let Some(entry) = self.table.get(&key).copied() {
+                let best_move = entry.best_move.transform(transform.inverse());
+                cached_move = Some(best_move);
+                // Narrowing the window at the root could leave several moves tied at the
+                // bound, so the root is always searched in full.
+                if ply > 0 && entry.depth >= depth {
+                    let score = from_table(entry.score, ply);
+                    match entry.bound {
+                        Bound::Exact => {
+                            *line = self.table_line(board, depth);
+                            return score;
+                        }
+                        Bound::Lower => alpha = alpha.max(score),
+                        Bound::Upper => beta = beta.min(score),
+                    }
+                    if alpha >= beta {
+                        line.push(best_move);
+                        return score;
+                    }
+                }
+            }
+        }
+
+        let original_alpha = alpha;
+        let mut best_score = i32::MIN;
+        let mut child_line = Vec::new();
+        for coord in self.ordered_moves(board, cached_move) {
+            let Ok(next) = board.after_move(coord) else {
+                continue;
+            };
+            let score = -self.negamax(&next, depth - 1, ply + 1, -beta, -alpha, &mut child_line);
+            if score > best_score {
+                best_score = score;
+                line.clear();
+                line.push(coord);
+                line.extend_from_slice(&child_line);
+            }
+            alpha = alpha.max(score);
+            if alpha >= beta {
+                break;
+            }
+        }
+
+        if self.config.transposition_table {
+            if let Some(&best_move) = line.first() {
+                let bound = if best_score <= original_alpha {
+                    Bound::Upper
+                } else if best_score >= beta {
+                    Bound::Lower
+                } else {
+                    Bound::Exact
+                };
+                self.table.insert(
+                    key,
+                    TableEntry {
+                        depth,
+                        score: to_table(best_score, ply),
+                        bound,
+                        best_move: best_move.transform(transform),
+                    },
+                );
+            }
+        }
+        best_score
+    }
+
+    /// Returns the legal moves of a position in the order they should be searched.
+    fn ordered_moves(&self, board: &Board, cached_move: Option<Coord>) -> Vec<Coord> {
+        let mut moves = board.legal_moves();
+        if self.config.move_ordering {
+            let priority = |coord: Coord| match (coord.row(), coord.col()) {
+                _ if Some(coord) == cached_move => 0,
+                (2, 2) => 1,
+                (1 | 3, 1 | 3) => 2,
+                _ => 3,
+            };
+            moves.sort_by_key(|&coord| priority(coord));
+        }
+        moves
+    }
+
+    /// Follows the cached best moves from a position to rebuild its principal variation.
+    fn table_line(&self, board: &Board, depth: usize) -> Vec<Coord> {
+        let mut line = Vec::new();
+        let mut board = *board;
+        while line.len() < depth {
+            let (canonical, transform) = board.canonical();
+            let key = BoardCodec::new().to_number(&canonical);
+            let Some(entry) = self.table.get(&key) else {
+                break;
+            };
+            let coord = entry.best_move.transform(transform.inverse());
+            let Ok(next) = board.after_move(coord) else {
+                break;
+            };
+            line.push(coord);
+            board = next;
+        }
+        line
+    }
+}
+
+/// Converts a score relative to the root into one relative to the position, for caching.
+const fn to_table(score: i32, ply: i32) -> i32 {
+    if score > WIN_THRESHOLD {
+        score + ply
+    } else if score < -WIN_THRESHOLD {
+        score - ply
+    } else {
+        score
+    }
+}
+
+/// Converts a cached score relative to the position into one relative to the root.
+const fn from_table(score: i32, ply: i32) -> i32 {
+    if score > WIN_THRESHOLD {
+        score - ply
+    } else if score < -WIN_THRESHOLD {
+        score + ply
+    } else {
+        score
+    }
+}
+
+/// An agent that plays the best move found by a [`Search`].
+#[derive(Debug, Clone)]
+pub struct SearchAgent<E = LineEvaluator> {
+    /// The search used to choose moves.
+    search: Search<E>,
+}
+
+impl<E: Evaluator> SearchAgent<E> {
+    /// Creates a new `SearchAgent`.
+    ///
+    /// # Arguments
+    ///
+    /// * `search` - The search used to choose moves.
+    pub const fn new(search: Search<E>) -> Self {
+        Self { search }
+    }
+
+    /// Returns the search used to choose moves.
+    pub const fn search(&self) -> &Search<E> {
+        &self.search
+    }
+}
+
+impl<E: Evaluator> Agent for SearchAgent<E> {
+    fn choose_move(&mut self, board: &Board, _symbol: Symbol) -> Option<Coord> {
+        self.search.search(board).best_move
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        GameResult,
+        Reduction,
+        Solver,
+        StateSpace,
+    };
+
+    #[test]
+    fn test_empty_board_is_a_draw() {
+        let mut search = Search::new(SearchConfig::default());
+        let result = search.search(&Board::new());
+        assert_eq!(result.score, 0);
+        assert!(result.best_move.is_some());
+        assert_eq!(result.principal_variation.len(), 9);
+    }
+
+    #[test]
+    fn test_finds_quickest_win() {
+        let mut search = Search::new(SearchConfig::default());
+        let result = search.search(&BoardCodec::new().decode("110220000").unwrap());
+        assert_eq!(result.best_move, Coord::new(1, 3).ok());
+        assert_eq!(result.score, WIN_SCORE - 1);
+        assert_eq!(result.principal_variation, vec![Coord::new(1, 3).unwrap()]);
+    }
+
+    #[test]
+    fn test_finished_game_has_no_move() {
+        let mut search = Search::new(SearchConfig::default());
+        let result = search.search(&BoardCodec::new().decode("111220000").unwrap());
+        assert_eq!(result.best_move, None);
+        assert_eq!(result.score, -WIN_SCORE);
+    }
+
+    #[test]
+    fn test_agrees_with_solver() {
+        let solver = Solver::new();
+        let configs = [
+            SearchConfig::default(),
+            SearchConfig {
+                transposition_table: false,
+                ..SearchConfig::default()
+            },
+            SearchConfig {
+                move_ordering: false,
+                ..SearchConfig::default()
+            },
+        ];
+
+        for config in configs {
+            let mut search = Search::new(config);
+            for board in StateSpace::enumerate(Reduction::Symmetry).iter() {
+                if board.status().is_over() {
+                    continue;
+                }
+                let result = search.search(board);
+                let evaluation = solver.evaluate(board).unwrap();
+                let expected = match evaluation.result {
+                    GameResult::Win => WIN_SCORE - i32::from(evaluation.distance),
+                    GameResult::Draw => 0,
+                    GameResult::Loss => i32::from(evaluation.distance) - WIN_SCORE,
+                };
+                assert_eq!(result.score, expected, "{board}");
+                assert!(
+                    solver.is_optimal(board, result.best_move.unwrap()),
+                    "{board}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_principal_variation_is_playable() {
+        let mut search = Search::new(SearchConfig::default());
+        let board = BoardCodec::new().decode("100000000").unwrap();
+        search.search(&Board::new());
+        let result = search.search(&board);
+
+        let mut position = board;
+        for coord in &result.principal_variation {
+            position = position.after_move(*coord).unwrap();
+        }
+        assert!(position.status().is_over());
+    }
+
+    #[test]
+    fn test_transposition_table_saves_nodes() {
+        let mut with_table = Search::new(SearchConfig::default());
+        let mut without_table = Search::new(SearchConfig {
+            transposition_table: false,
+            ..SearchConfig::default()
+        });
+
+        let cached = with_table.search(&Board::new()).nodes;
+        let uncached = without_table.search(&Board::new()).nodes;
+        assert!(cached < uncached);
+    }
+
+    #[test]
+    fn test_depth_limited_search_uses_evaluator() {
+        let mut search = Search::with_evaluator(
+            SearchConfig {
+                max_depth: Some(1),
+                ..SearchConfig::default()
+            },
+            |board: &Board, _symbol: Symbol| {
+                if board.get_square(3, 3).is_empty() {
+                    0
+                } else {
+                    // The opponent is to move after the first player took the corner.
+                    -50
+                }
+            },
+        );
+        let result = search.search(&Board::new());
+        assert_eq!(result.best_move, Coord::new(3, 3).ok());
+        assert_eq!(result.score, 50);
+        assert_eq!(result.nodes, 10);
+    }
+
+    #[test]
+    fn test_line_evaluator() {
+        let board = BoardCodec::new().decode("110020000").unwrap();
+        assert_eq!(
+            LineEvaluator.evaluate(&board, Symbol::X),
+            -LineEvaluator.evaluate(&board, Symbol::O)
+        );
+        assert!(LineEvaluator.evaluate(&board, Symbol::X) > 0);
+    }
+}
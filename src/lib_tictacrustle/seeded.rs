@@ -0,0 +1,63 @@
+// SPDX-FileCopyrightText: 2022 - 2024 Ali Sajid Imami
+//
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+
+/// The random number generator every [`Seeded`] component draws from.
+///
+/// It is a named generator rather than `rand`'s `StdRng`, whose algorithm may change between
+/// releases and platforms, so a fixed seed yields the same games everywhere.
+pub type SeededRng = ChaCha8Rng;
+
+/// A stochastic component whose random number generator can be replaced, so that its behaviour
+/// is reproducible.
+///
+/// Every agent that makes random choices, and the [`Trainer`](crate::Trainer), is created seeded
+/// from the operating system and implements this trait. Fixing the seed of each one makes a game
+/// or a training run reproducible bit for bit.
+///
+/// # Examples
+///
+/// ```
+/// use tictacrustle::{
+///     play_match,
+///     RandomAgent,
+///     Seeded,
+/// };
+///
+/// let play = || {
+///     let mut first = RandomAgent::new().with_seed(1);
+///     let mut second = RandomAgent::new().with_seed(2);
+///     play_match(&mut first, &mut second).unwrap()
+/// };
+///
+/// assert_eq!(play(), play());
+/// ```
+pub trait Seeded: Sized {
+    /// Returns the random number generator the component draws from.
+    fn rng_mut(&mut self) -> &mut SeededRng;
+
+    /// Replaces the random number generator the component draws from.
+    ///
+    /// # Arguments
+    ///
+    /// * `rng` - The random number generator to draw from from now on.
+    #[must_use]
+    fn with_rng(mut self, rng: SeededRng) -> Self {
+        *self.rng_mut() = rng;
+        self
+    }
+
+    /// Replaces the random number generator with one seeded from `seed`.
+    ///
+    /// # Arguments
+    ///
+    /// * `seed` - The seed of the random number generator.
+    #[must_use]
+    fn with_seed(self, seed: u64) -> Self {
+        self.with_rng(SeededRng::seed_from_u64(seed))
+    }
+}
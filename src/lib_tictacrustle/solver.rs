@@ -0,0 +1,320 @@
+// SPDX-FileCopyrightText: 2022 - 2024 Ali Sajid Imami
+//
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use std::{
+    cmp::Ordering,
+    fmt,
+};
+
+use crate::{
+    Board,
+    BoardCodec,
+    Coord,
+    GameResult,
+    GameStatus,
+};
+
+/// The number of distinct boards that can be encoded, `3^9`.
+const TABLE_SIZE: usize = 19_683;
+
+/// The game-theoretic value of a position for the player to move, under perfect play by both
+/// sides.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Evaluation {
+    /// The result the player to move can force.
+    pub result:   GameResult,
+    /// The number of moves until the game ends, assuming the winner wins as quickly as possible
+    /// and the loser holds out as long as possible.
+    pub distance: u8,
+}
+
+impl Evaluation {
+    /// Returns the evaluation of the parent position, given the evaluation of the position after
+    /// the player to move has played.
+    #[must_use]
+    pub const fn parent(self) -> Self {
+        Self {
+            result:   self.result.opposite(),
+            distance: self.distance + 1,
+        }
+    }
+
+    /// Returns a score where a higher value is better for the player to move: quicker wins beat
+    /// slower wins, any win beats a draw, and slower losses beat quicker losses.
+    #[must_use]
+    pub fn score(self) -> i32 {
+        let distance = i32::from(self.distance);
+        match self.result {
+            GameResult::Win => 100 - distance,
+            GameResult::Draw => 0,
+            GameResult::Loss => distance - 100,
+        }
+    }
+}
+
+impl PartialOrd for Evaluation {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Evaluation {
+    /// Orders evaluations from the point of view of the player to move, so the best evaluation
+    /// is the greatest. Draws, which share a score, are ordered by distance so that the ordering
+    /// agrees with equality.
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.score()
+            .cmp(&other.score())
+            .then_with(|| self.distance.cmp(&other.distance))
+    }
+}
+
+impl fmt::Display for Evaluation {
+    /// Formats the evaluation, such as `Win in 3` or `Draw in 9`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} in {}", self.result, self.distance)
+    }
+}
+
+/// A perfect-play solver holding the exact value of every reachable position.
+///
+/// The whole game tree is solved with minimax when the solver is created, and the value of each
+/// position is cached in a table indexed by its [`BoardCodec::to_number`]. The solver is the
+/// ground truth for scoring other agents, and the oracle that perfect opponents play from.
+///
+/// # Examples
+///
+/// ```
+/// use tictacrustle::{
+///     Board,
+///     GameResult,
+///     Solver,
+/// };
+///
+/// let solver = Solver::new();
+/// let evaluation = solver.evaluate(&Board::new()).unwrap();
+/// assert_eq!(evaluation.result, GameResult::Draw);
+/// assert_eq!(solver.best_moves(&Board::new()).len(), 9);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Solver {
+    /// The value of each reachable position, indexed by its base-3 number.
+    table: Vec<Option<Evaluation>>,
+}
+
+impl Solver {
+    /// Creates a new `Solver` by solving every position reachable from the empty board.
+    #[must_use]
+    pub fn new() -> Self {
+        let mut solver = Self {
+            table: vec![None; TABLE_SIZE],
+        };
+        solver.solve(&Board::new());
+        solver
+    }
+
+    /// Returns the value of a position for the player to move.
+    ///
+    /// # Returns
+    ///
+    /// The [`Evaluation`] of the position, or `None` if the position cannot be reached in a
+    /// legal game.
+    #[must_use]
+    pub fn evaluate(&self, board: &Board) -> Option<Evaluation> {
+        self.table[usize::from(BoardCodec::new().to_number(board))]
+    }
+
+    /// Returns every move that achieves the value of the position.
+    ///
+    /// Moves are compared by [`Evaluation::score`], so every move that holds a draw is optimal
+    /// in a drawn position, however long the game lasts.
+    ///
+    /// # Returns
+    ///
+    /// The optimal moves in row-major order, or an empty vector if the game is over or the
+    /// position cannot be reached in a legal game.
+    #[must_use]
+    pub fn best_moves(&self, board: &Board) -> Vec<Coord> {
+        let Some(best) = self.evaluate(board) else {
+            return Vec::new();
+        };
+        board
+            .legal_moves()
+            .into_iter()
+            .filter(|&coord| {
+                self.move_evaluation(board, coord)
+                    .is_some_and(|evaluation| evaluation.score() == best.score())
+            })
+            .collect()
+    }
+
+    /// Returns the value, for the player to move, of playing a given move.
+    ///
+    /// # Returns
+    ///
+    /// The [`Evaluation`] of the move, or `None` if the move is illegal or the position cannot
+    /// be reached in a legal game.
+    #[must_use]
+    pub fn move_evaluation(&self, board: &Board, coord: Coord) -> Option<Evaluation> {
+        let next = board.after_move(coord).ok()?;
+        self.evaluate(&next).map(Evaluation::parent)
+    }
+
+    /// Checks whether a move achieves the value of the position.
+    #[must_use]
+    pub fn is_optimal(&self, board: &Board, coord: Coord) -> bool {
+        self.best_moves(board).contains(&coord)
+    }
+
+    /// Returns the number of solved positions.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.table.iter().flatten().count()
+    }
+
+    /// Checks whether the solver holds no positions. This is never the case once created.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Solves a position and every position reachable from it, caching the results.
+    fn solve(&mut self, board: &Board) -> Evaluation {
+        let index = usize::from(BoardCodec::new().to_number(board));
+        if let Some(evaluation) = self.table[index] {
+            return evaluation;
+        }
+
+        let evaluation = match board.status() {
+            GameStatus::Won { .. } => Evaluation {
+                result:   GameResult::Loss,
+                distance: 0,
+            },
+            GameStatus::Draw => Evaluation {
+                result:   GameResult::Draw,
+                distance: 0,
+            },
+            GameStatus::InProgress { .. } => board
+                .legal_moves()
+                .into_iter()
+                .filter_map(|coord| board.after_move(coord).ok())
+                .map(|next| self.solve(&next).parent())
+                .max()
+                .unwrap_or(Evaluation {
+                    result:   GameResult::Draw,
+                    distance: 0,
+                }),
+        };
+        self.table[index] = Some(evaluation);
+        evaluation
+    }
+}
+
+impl Default for Solver {
+    /// Creates a solved table by calling [`Solver::new`].
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_solves_every_reachable_position() {
+        let solver = Solver::new();
+        assert_eq!(solver.len(), 5478);
+        assert_eq!(
+            solver.evaluate(&Board::new()),
+            Some(Evaluation {
+                result:   GameResult::Draw,
+                distance: 9,
+            })
+        );
+        assert_eq!(
+            solver.evaluate(&BoardCodec::new().decode("110000000").unwrap()),
+            None
+        );
+    }
+
+    #[test]
+    fn test_finished_positions() {
+        let solver = Solver::new();
+        assert_eq!(
+            solver.evaluate(&BoardCodec::new().decode("111220000").unwrap()),
+            Some(Evaluation {
+                result:   GameResult::Loss,
+                distance: 0,
+            })
+        );
+        assert!(solver
+            .best_moves(&BoardCodec::new().decode("111220000").unwrap())
+            .is_empty());
+    }
+
+    #[test]
+    fn test_takes_immediate_win() {
+        let solver = Solver::new();
+        // X to move can win on the top row.
+        let board = BoardCodec::new().decode("110220000").unwrap();
+        assert_eq!(
+            solver.evaluate(&board),
+            Some(Evaluation {
+                result:   GameResult::Win,
+                distance: 1,
+            })
+        );
+        assert_eq!(solver.best_moves(&board), vec![Coord::new(1, 3).unwrap()]);
+    }
+
+    #[test]
+    fn test_blocks_threat() {
+        let solver = Solver::new();
+        // O to move must block X on the top row.
+        let board = BoardCodec::new().decode("110020000").unwrap();
+        assert_eq!(solver.best_moves(&board), vec![Coord::new(1, 3).unwrap()]);
+        assert!(solver.is_optimal(&board, Coord::new(1, 3).unwrap()));
+        assert!(!solver.is_optimal(&board, Coord::new(3, 3).unwrap()));
+    }
+
+    #[test]
+    fn test_edge_reply_to_corner_loses() {
+        let solver = Solver::new();
+        // After X takes a corner, O must take the centre to draw.
+        let board = BoardCodec::new().decode("100000000").unwrap();
+        assert_eq!(solver.best_moves(&board), vec![Coord::new(2, 2).unwrap()]);
+        assert_eq!(
+            solver
+                .move_evaluation(&board, Coord::new(1, 2).unwrap())
+                .map(|e| e.result),
+            Some(GameResult::Loss)
+        );
+    }
+
+    #[test]
+    fn test_evaluation_ordering() {
+        let win = |distance| Evaluation {
+            result: GameResult::Win,
+            distance,
+        };
+        let loss = |distance| Evaluation {
+            result: GameResult::Loss,
+            distance,
+        };
+        let draw = |distance| Evaluation {
+            result: GameResult::Draw,
+            distance,
+        };
+        assert!(win(1) > win(3));
+        assert!(win(7) > draw(4));
+        assert!(draw(4) > loss(8));
+        assert!(loss(6) > loss(2));
+
+        assert_ne!(draw(2), draw(4));
+        assert_ne!(draw(2).cmp(&draw(4)), Ordering::Equal);
+        assert_eq!(draw(4).cmp(&draw(4)), Ordering::Equal);
+    }
+}
@@ -39,7 +39,7 @@ use crate::SquareValue;
 /// assert!(o_square.is_empty());
 //# assert_eq!(o_square.value, SquareValue::O);
 /// ```
-#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
 pub struct Square {
     /// The current state of the square.
     /// This is a variant of the `SquareValue` enum.
@@ -5,6 +5,8 @@
 
 use std::fmt;
 
+use crate::Symbol;
+
 /// Enum `SquareValue` represents the state of a square in a Tic-Tac-Toe game.
 ///
 /// A square on the Tic-Tac-Toe board can be in one of three states: it can be empty, or it can
@@ -31,7 +33,7 @@ use std::fmt;
 ///
 /// The `SquareValue` enum implements the `Display` trait, allowing it to be easily printed for
 /// debugging or user interface purposes.
-#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
 pub enum SquareValue {
     /// An empty square, which neither player has marked.
     Empty,
@@ -41,6 +43,40 @@ pub enum SquareValue {
     O,
 }
 
+impl SquareValue {
+    /// Returns the player symbol held by the square, if any.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tictacrustle::{
+    ///     SquareValue,
+    ///     Symbol,
+    /// };
+    ///
+    /// assert_eq!(SquareValue::X.symbol(), Some(Symbol::X));
+    /// assert_eq!(SquareValue::Empty.symbol(), None);
+    /// ```
+    #[must_use]
+    pub const fn symbol(self) -> Option<Symbol> {
+        match self {
+            Self::X => Some(Symbol::X),
+            Self::O => Some(Symbol::O),
+            Self::Empty => None,
+        }
+    }
+}
+
+impl From<Symbol> for SquareValue {
+    /// Converts a player symbol to the value of a square marked by that player.
+    fn from(symbol: Symbol) -> Self {
+        match symbol {
+            Symbol::X => Self::X,
+            Symbol::O => Self::O,
+        }
+    }
+}
+
 /// `SquareValue` is an enum that represents the possible values of a square in a Tic-Tac-Toe game.
 /// It can be either `X`, `O`, or `Empty` if the square is not yet filled.
 ///
@@ -85,4 +121,11 @@ mod tests {
         assert_eq!(SquareValue::O, SquareValue::O);
         assert_eq!(SquareValue::Empty, SquareValue::Empty);
     }
+
+    #[test]
+    fn test_symbol_round_trip() {
+        assert_eq!(SquareValue::from(Symbol::X).symbol(), Some(Symbol::X));
+        assert_eq!(SquareValue::from(Symbol::O).symbol(), Some(Symbol::O));
+        assert_eq!(SquareValue::Empty.symbol(), None);
+    }
 }
@@ -0,0 +1,256 @@
+// SPDX-FileCopyrightText: 2022 - 2024 Ali Sajid Imami
+//
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use std::collections::HashSet;
+
+use crate::{
+    Board,
+    GameStatus,
+    Symbol,
+};
+
+/// Selects whether symmetric boards are counted as one position or as separate positions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Reduction {
+    /// Every board is a separate position, as in MENACE-S.
+    None,
+    /// Boards that are rotations or reflections of each other are a single position, as in
+    /// MENACE-C. Positions are stored in their [`Board::canonical`] form.
+    Symmetry,
+}
+
+/// The number of positions in each possible state of the game.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct OutcomeCounts {
+    /// Positions where the game is still being played.
+    pub in_progress: usize,
+    /// Positions where `X` has completed a line.
+    pub x_wins:      usize,
+    /// Positions where `O` has completed a line.
+    pub o_wins:      usize,
+    /// Full boards where neither player has completed a line.
+    pub draws:       usize,
+}
+
+/// Every position that can be reached in a legal game, starting from the empty board.
+///
+/// The positions are produced by walking every legal game from [`Board::new`] one ply at a time,
+/// so they are ordered by the number of moves played. This is used to size MENACE databases, to
+/// test encodings exhaustively, and as the set of boards that [`Board::is_reachable`] accepts.
+///
+/// # Examples
+///
+/// ```
+/// use tictacrustle::{
+///     Reduction,
+///     StateSpace,
+/// };
+///
+/// assert_eq!(StateSpace::enumerate(Reduction::None).len(), 5478);
+/// assert_eq!(StateSpace::enumerate(Reduction::Symmetry).len(), 765);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StateSpace {
+    /// How symmetric positions were treated during enumeration.
+    reduction: Reduction,
+    /// The reachable positions, ordered by ply.
+    positions: Vec<Board>,
+}
+
+impl StateSpace {
+    /// Walks every legal game from the empty board and collects each distinct position.
+    ///
+    /// # Arguments
+    ///
+    /// * `reduction` - Whether symmetric boards are collapsed into their canonical form.
+    ///
+    /// # Returns
+    ///
+    /// The set of reachable positions, ordered by ply.
+    #[must_use]
+    pub fn enumerate(reduction: Reduction) -> Self {
+        let mut positions = Vec::new();
+        let mut layer = vec![Board::new()];
+
+        while !layer.is_empty() {
+            let mut seen = HashSet::new();
+            let mut next_layer = Vec::new();
+            for board in &layer {
+                for coord in board.legal_moves() {
+                    let Ok(next) = board.after_move(coord) else {
+                        continue;
+                    };
+                    let next = match reduction {
+                        Reduction::None => next,
+                        Reduction::Symmetry => next.canonical().0,
+                    };
+                    if seen.insert(next) {
+                        next_layer.push(next);
+                    }
+                }
+            }
+            positions.append(&mut layer);
+            layer = next_layer;
+        }
+
+        Self {
+            reduction,
+            positions,
+        }
+    }
+
+    /// Returns how symmetric positions were treated during enumeration.
+    #[must_use]
+    pub const fn reduction(&self) -> Reduction {
+        self.reduction
+    }
+
+    /// Returns the number of reachable positions.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.positions.len()
+    }
+
+    /// Checks whether the state space contains no positions. This is never the case, since the
+    /// empty board is always reachable.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.positions.is_empty()
+    }
+
+    /// Returns an iterator over the reachable positions, ordered by ply.
+    pub fn iter(&self) -> impl Iterator<Item = &Board> {
+        self.positions.iter()
+    }
+
+    /// Returns the reachable positions where the given player is to move and the game is not
+    /// over. These are the positions a MENACE playing that side needs a matchbox for.
+    pub fn positions_to_move(&self, symbol: Symbol) -> impl Iterator<Item = &Board> {
+        self.positions
+            .iter()
+            .filter(move |board| board.status() == GameStatus::InProgress { to_move: symbol })
+    }
+
+    /// Counts the reachable positions by the number of moves played.
+    ///
+    /// # Returns
+    ///
+    /// An array where index `n` holds the number of positions after `n` moves.
+    #[must_use]
+    pub fn count_by_ply(&self) -> [usize; 10] {
+        let mut counts = [0; 10];
+        for board in &self.positions {
+            counts[board.ply()] += 1;
+        }
+        counts
+    }
+
+    /// Counts the reachable positions by the state of the game.
+    #[must_use]
+    pub fn count_by_outcome(&self) -> OutcomeCounts {
+        let mut counts = OutcomeCounts::default();
+        for board in &self.positions {
+            match board.status() {
+                GameStatus::InProgress { .. } => counts.in_progress += 1,
+                GameStatus::Won {
+                    symbol: Symbol::X, ..
+                } => counts.x_wins += 1,
+                GameStatus::Won {
+                    symbol: Symbol::O, ..
+                } => counts.o_wins += 1,
+                GameStatus::Draw => counts.draws += 1,
+            }
+        }
+        counts
+    }
+}
+
+impl<'a> IntoIterator for &'a StateSpace {
+    type IntoIter = std::slice::Iter<'a, Board>;
+    type Item = &'a Board;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.positions.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::BoardCodec;
+
+    #[test]
+    fn test_count_by_ply() {
+        let space = StateSpace::enumerate(Reduction::None);
+        assert_eq!(
+            space.count_by_ply(),
+            [1, 9, 72, 252, 756, 1260, 1520, 1140, 390, 78]
+        );
+    }
+
+    #[test]
+    fn test_count_by_ply_with_symmetry() {
+        let space = StateSpace::enumerate(Reduction::Symmetry);
+        assert_eq!(
+            space.count_by_ply(),
+            [1, 3, 12, 38, 108, 174, 204, 153, 57, 15]
+        );
+    }
+
+    #[test]
+    fn test_count_by_outcome() {
+        let space = StateSpace::enumerate(Reduction::None);
+        assert_eq!(
+            space.count_by_outcome(),
+            OutcomeCounts {
+                in_progress: 4520,
+                x_wins:      626,
+                o_wins:      316,
+                draws:       16,
+            }
+        );
+    }
+
+    #[test]
+    fn test_count_by_outcome_with_symmetry() {
+        let space = StateSpace::enumerate(Reduction::Symmetry);
+        assert_eq!(
+            space.count_by_outcome(),
+            OutcomeCounts {
+                in_progress: 627,
+                x_wins:      91,
+                o_wins:      44,
+                draws:       3,
+            }
+        );
+    }
+
+    #[test]
+    fn test_positions_to_move() {
+        // The classical MENACE used one matchbox for every distinct position, up to symmetry,
+        // where the first player is to move, the game is not over, and more than one square is
+        // still free.
+        let space = StateSpace::enumerate(Reduction::Symmetry);
+        let boxes = space
+            .positions_to_move(Symbol::X)
+            .filter(|board| board.ply() < 8)
+            .count();
+        assert_eq!(boxes, 304);
+    }
+
+    #[test]
+    fn test_reachable_matches_every_encodable_board() {
+        let codec = BoardCodec::new();
+        let space: HashSet<Board> = StateSpace::enumerate(Reduction::None)
+            .iter()
+            .copied()
+            .collect();
+
+        for number in 0..19_683 {
+            let board = codec.from_number(number).unwrap();
+            assert_eq!(board.is_reachable(), space.contains(&board), "{board}");
+        }
+    }
+}
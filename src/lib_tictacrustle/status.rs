@@ -0,0 +1,222 @@
+// SPDX-FileCopyrightText: 2022 - 2024 Ali Sajid Imami
+//
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use std::fmt;
+
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+use crate::{
+    Line,
+    Symbol,
+};
+
+/// Represents the state of a game of Tic Tac Toe at a given point in time.
+///
+/// Unlike [`Game::winner`](crate::Game::winner), a `GameStatus` distinguishes between a game that
+/// is still being played and a game that has ended in a draw.
+///
+/// # Examples
+///
+/// ```
+/// use tictacrustle::{
+///     Board,
+///     GameStatus,
+///     Symbol,
+/// };
+///
+/// let board = Board::new();
+/// assert_eq!(
+///     board.status(),
+///     GameStatus::InProgress { to_move: Symbol::X }
+/// );
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GameStatus {
+    /// The game is still being played.
+    InProgress {
+        /// The symbol of the player who makes the next move.
+        to_move: Symbol,
+    },
+    /// A player has completed a line and won the game.
+    Won {
+        /// The symbol of the winning player.
+        symbol: Symbol,
+        /// The line completed by the winning player.
+        line:   Line,
+    },
+    /// Every square is filled and no player has won.
+    Draw,
+}
+
+impl GameStatus {
+    /// Checks whether the game has ended, either with a winner or in a draw.
+    #[must_use]
+    pub const fn is_over(&self) -> bool {
+        !matches!(self, Self::InProgress { .. })
+    }
+
+    /// Returns the symbol of the winning player, if any.
+    #[must_use]
+    pub const fn winner(&self) -> Option<Symbol> {
+        match self {
+            Self::Won { symbol, .. } => Some(*symbol),
+            _ => None,
+        }
+    }
+
+    /// Returns the symbol of the player who makes the next move, if the game is still in progress.
+    #[must_use]
+    pub const fn to_move(&self) -> Option<Symbol> {
+        match self {
+            Self::InProgress { to_move } => Some(*to_move),
+            _ => None,
+        }
+    }
+}
+
+/// The result of a finished game from the point of view of one player.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GameResult {
+    /// The player completed a line.
+    Win,
+    /// The game ended in a draw.
+    Draw,
+    /// The opponent completed a line.
+    Loss,
+}
+
+impl GameResult {
+    /// Determines the result of a game for one of the players.
+    ///
+    /// # Arguments
+    ///
+    /// * `status` - The status of the game.
+    /// * `symbol` - The symbol of the player whose result is wanted.
+    ///
+    /// # Returns
+    ///
+    /// `Some(GameResult)` if the game is over, `None` if it is still in progress.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tictacrustle::{
+    ///     GameResult,
+    ///     GameStatus,
+    ///     Symbol,
+    /// };
+    ///
+    /// assert_eq!(
+    ///     GameResult::for_player(&GameStatus::Draw, Symbol::O),
+    ///     Some(GameResult::Draw)
+    /// );
+    /// ```
+    #[must_use]
+    pub fn for_player(status: &GameStatus, symbol: Symbol) -> Option<Self> {
+        match status {
+            GameStatus::InProgress { .. } => None,
+            GameStatus::Won { symbol: winner, .. } if *winner == symbol => Some(Self::Win),
+            GameStatus::Won { .. } => Some(Self::Loss),
+            GameStatus::Draw => Some(Self::Draw),
+        }
+    }
+
+    /// Returns the result of the same game for the other player.
+    #[must_use]
+    pub const fn opposite(self) -> Self {
+        match self {
+            Self::Win => Self::Loss,
+            Self::Draw => Self::Draw,
+            Self::Loss => Self::Win,
+        }
+    }
+}
+
+impl fmt::Display for GameResult {
+    /// Formats the result as `Win`, `Draw` or `Loss`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Win => write!(f, "Win"),
+            Self::Draw => write!(f, "Draw"),
+            Self::Loss => write!(f, "Loss"),
+        }
+    }
+}
+
+impl fmt::Display for GameStatus {
+    /// Formats the status as a short human readable sentence.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InProgress { to_move } => write!(f, "{to_move} to move"),
+            Self::Won { symbol, line } => write!(f, "{symbol} wins on {line}"),
+            Self::Draw => write!(f, "Draw"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_in_progress() {
+        let status = GameStatus::InProgress { to_move: Symbol::O };
+        assert!(!status.is_over());
+        assert_eq!(status.to_move(), Some(Symbol::O));
+        assert_eq!(status.winner(), None);
+    }
+
+    #[test]
+    fn test_won() {
+        let status = GameStatus::Won {
+            symbol: Symbol::X,
+            line:   Line::MainDiagonal,
+        };
+        assert!(status.is_over());
+        assert_eq!(status.to_move(), None);
+        assert_eq!(status.winner(), Some(Symbol::X));
+        assert_eq!(status.to_string(), "X wins on main diagonal");
+    }
+
+    #[test]
+    fn test_draw() {
+        assert!(GameStatus::Draw.is_over());
+        assert_eq!(GameStatus::Draw.winner(), None);
+        assert_eq!(GameStatus::Draw.to_move(), None);
+    }
+
+    #[test]
+    fn test_result_for_player() {
+        let won = GameStatus::Won {
+            symbol: Symbol::O,
+            line:   Line::column(1).unwrap(),
+        };
+        assert_eq!(
+            GameResult::for_player(&won, Symbol::O),
+            Some(GameResult::Win)
+        );
+        assert_eq!(
+            GameResult::for_player(&won, Symbol::X),
+            Some(GameResult::Loss)
+        );
+        assert_eq!(
+            GameResult::for_player(&GameStatus::Draw, Symbol::X),
+            Some(GameResult::Draw)
+        );
+        let in_progress = GameStatus::InProgress { to_move: Symbol::X };
+        assert_eq!(GameResult::for_player(&in_progress, Symbol::X), None);
+    }
+
+    #[test]
+    fn test_result_opposite() {
+        assert_eq!(GameResult::Win.opposite(), GameResult::Loss);
+        assert_eq!(GameResult::Draw.opposite(), GameResult::Draw);
+        assert_eq!(GameResult::Loss.opposite(), GameResult::Win);
+    }
+}
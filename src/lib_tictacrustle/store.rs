@@ -0,0 +1,85 @@
+// SPDX-FileCopyrightText: 2022 - 2024 Ali Sajid Imami
+//
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+mod memory;
+#[cfg(feature = "sqlite")]
+mod sqlite;
+
+pub use self::memory::MemoryStore;
+#[cfg(feature = "sqlite")]
+pub use self::sqlite::{
+    SqliteStore,
+    SCHEMA_VERSION,
+};
+use crate::{
+    Matchbox,
+    Menace,
+    MenaceConfig,
+    PersistenceError,
+};
+
+/// A place where MENACE's matchboxes are kept between games.
+///
+/// The README's design has a database replace Michie's physical matchboxes. A store holds the
+/// settings of one MENACE, the number of games it has played, and its matchboxes keyed by
+/// [`Menace::key`]. After each game only the matchboxes that were drawn from are written, in a
+/// single [`MatchboxStore::commit_game`], so a long-running server never rewrites the whole
+/// engine.
+pub trait MatchboxStore {
+    /// Returns the saved settings, or `None` if nothing has been saved yet.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`PersistenceError`] if the store cannot be read.
+    fn config(&self) -> Result<Option<MenaceConfig>, PersistenceError>;
+
+    /// Returns the number of games that have been committed.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`PersistenceError`] if the store cannot be read.
+    fn games_played(&self) -> Result<u64, PersistenceError>;
+
+    /// Returns the matchbox with the given key, if it has been saved.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`PersistenceError`] if the store cannot be read.
+    fn matchbox(&self, key: u16) -> Result<Option<Matchbox>, PersistenceError>;
+
+    /// Returns every saved matchbox along with its key, in ascending key order.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`PersistenceError`] if the store cannot be read.
+    fn matchboxes(&self) -> Result<Vec<(u16, Matchbox)>, PersistenceError>;
+
+    /// Replaces everything in the store with the settings, game count and matchboxes of a
+    /// MENACE.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`PersistenceError`] if the store cannot be written. Nothing is changed in
+    /// that case.
+    fn save(&mut self, menace: &Menace) -> Result<(), PersistenceError>;
+
+    /// Records the end of a game: the new game count and the matchboxes that changed.
+    ///
+    /// Either everything is written or, on error, nothing is.
+    ///
+    /// # Arguments
+    ///
+    /// * `games_played` - The number of games played, including this one.
+    /// * `matchboxes` - The matchboxes drawn from during the game, after reinforcement.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`PersistenceError`] if the store cannot be written.
+    fn commit_game(
+        &mut self,
+        games_played: u64,
+        matchboxes: &[(u16, Matchbox)],
+    ) -> Result<(), PersistenceError>;
+}
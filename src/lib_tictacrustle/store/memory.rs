@@ -0,0 +1,148 @@
+// SPDX-FileCopyrightText: 2022 - 2024 Ali Sajid Imami
+//
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use std::collections::BTreeMap;
+
+use super::MatchboxStore;
+use crate::{
+    Matchbox,
+    Menace,
+    MenaceConfig,
+    PersistenceError,
+};
+
+/// A [`MatchboxStore`] that keeps everything in memory.
+///
+/// Nothing survives the process, which makes it the store of choice for tests and short
+/// experiments.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MemoryStore {
+    /// The saved settings, if any.
+    config:       Option<MenaceConfig>,
+    /// The number of games committed.
+    games_played: u64,
+    /// The saved matchboxes, keyed by [`Menace::key`].
+    boxes:        BTreeMap<u16, Matchbox>,
+}
+
+impl MemoryStore {
+    /// Creates a new, empty `MemoryStore`.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl MatchboxStore for MemoryStore {
+    fn config(&self) -> Result<Option<MenaceConfig>, PersistenceError> {
+        Ok(self.config)
+    }
+
+    fn games_played(&self) -> Result<u64, PersistenceError> {
+        Ok(self.games_played)
+    }
+
+    fn matchbox(&self, key: u16) -> Result<Option<Matchbox>, PersistenceError> {
+        Ok(self.boxes.get(&key).copied())
+    }
+
+    fn matchboxes(&self) -> Result<Vec<(u16, Matchbox)>, PersistenceError> {
+        Ok(self
+            .boxes
+            .iter()
+            .map(|(&key, &matchbox)| (key, matchbox))
+            .collect())
+    }
+
+    fn save(&mut self, menace: &Menace) -> Result<(), PersistenceError> {
+        self.config = Some(*menace.config());
+        self.games_played = menace.games_played();
+        self.boxes = menace
+            .boxes()
+            .map(|(key, &matchbox)| (key, matchbox))
+            .collect();
+        Ok(())
+    }
+
+    fn commit_game(
+        &mut self,
+        games_played: u64,
+        matchboxes: &[(u16, Matchbox)],
+    ) -> Result<(), PersistenceError> {
+        self.games_played = games_played;
+        self.boxes.extend(matchboxes.iter().copied());
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use rand::SeedableRng;
+    use rstest::rstest;
+
+    use super::*;
+    use crate::{
+        Board,
+        BoardCodec,
+        Flavor,
+        GameResult,
+        PersistenceError,
+        SeededRng,
+    };
+
+    #[test]
+    fn test_empty_store_gives_new_menace() {
+        let store = MemoryStore::new();
+        assert_eq!(Menace::from_store(&store).unwrap(), Menace::new());
+    }
+
+    #[test]
+    fn test_commits_only_drawn_matchboxes() {
+        let mut rng = SeededRng::seed_from_u64(1);
+        let mut store = MemoryStore::new();
+        let mut menace = Menace::with_flavor(Flavor::Symmetric);
+        menace.save_to(&mut store).unwrap();
+
+        let board = Board::new();
+        let coord = menace.choose_move(&board, &mut rng).unwrap();
+        let reply = board.after_move(coord).unwrap();
+        menace.choose_move(&reply, &mut rng).unwrap();
+        menace.reinforce_into(GameResult::Loss, &mut store).unwrap();
+
+        assert_eq!(store.games_played().unwrap(), 1);
+        assert_eq!(store.matchboxes().unwrap().len(), 2);
+        assert_eq!(
+            store.matchbox(menace.key(&board)).unwrap(),
+            menace.matchbox(&board).copied()
+        );
+        assert_eq!(Menace::from_store(&store).unwrap(), menace);
+    }
+
+    #[rstest]
+    #[case(Flavor::Classic, "001000000", 0)]
+    #[case(Flavor::Symmetric, "111222000", 0)]
+    #[case(Flavor::Classic, "100000000", 1)]
+    #[case(Flavor::Symmetric, "111220000", 0)]
+    #[case(Flavor::Classic, "000000000", u32::MAX)]
+    fn test_rejects_invalid_matchboxes(
+        #[case] flavor: Flavor,
+        #[case] code: &str,
+        #[case] beads: u32,
+    ) {
+        let codec = BoardCodec::new();
+        let key = codec.to_number(&codec.decode(code).unwrap());
+        let mut store = MemoryStore::new();
+        Menace::with_flavor(flavor).save_to(&mut store).unwrap();
+        store
+            .commit_game(1, &[(key, Matchbox::from_beads([beads; 9]))])
+            .unwrap();
+
+        assert_eq!(
+            Menace::from_store(&store),
+            Err(PersistenceError::InvalidMatchbox(key.to_string()))
+        );
+    }
+}
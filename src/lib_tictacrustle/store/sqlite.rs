@@ -0,0 +1,334 @@
+// SPDX-FileCopyrightText: 2022 - 2024 Ali Sajid Imami
+//
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use std::path::Path;
+
+use rusqlite::{
+    params,
+    Connection,
+    OptionalExtension,
+    Transaction,
+};
+
+use super::MatchboxStore;
+use crate::{
+    BoardCodec,
+    Matchbox,
+    Menace,
+    MenaceConfig,
+    PersistenceError,
+};
+
+/// The migrations that build the schema, in order. The schema version of a database is the
+/// number of migrations applied to it, and is kept in SQLite's `user_version`.
+const MIGRATIONS: &[&str] = &["
+    CREATE TABLE menace (
+        id           INTEGER PRIMARY KEY CHECK (id = 1),
+        config       TEXT,
+        games_played INTEGER NOT NULL
+    );
+    INSERT INTO menace (id, config, games_played) VALUES (1, NULL, 0);
+    CREATE TABLE matchboxes (
+        key   INTEGER PRIMARY KEY,
+        board TEXT NOT NULL,
+        beads TEXT NOT NULL
+    );
+"];
+
+/// The schema version written by this library.
+#[allow(clippy::cast_possible_truncation)]
+pub const SCHEMA_VERSION: u32 = MIGRATIONS.len() as u32;
+
+/// A [`MatchboxStore`] backed by an SQLite database.
+///
+/// The settings and game count live in a single-row `menace` table, and every matchbox is a row
+/// of the `matchboxes` table holding its [`Menace::key`], its board code for people reading the
+/// database, and its beads as a JSON array. The schema is created, or migrated from an older
+/// version, when the store is opened.
+///
+/// # Examples
+///
+/// ```
+/// use tictacrustle::{
+///     MatchboxStore,
+///     Menace,
+///     SqliteStore,
+///     SCHEMA_VERSION,
+/// };
+///
+/// let mut store = SqliteStore::open_in_memory().unwrap();
+/// assert_eq!(store.schema_version().unwrap(), SCHEMA_VERSION);
+///
+/// Menace::new().save_to(&mut store).unwrap();
+/// assert_eq!(store.games_played().unwrap(), 0);
+/// ```
+#[derive(Debug)]
+pub struct SqliteStore {
+    /// The open database.
+    connection: Connection,
+}
+
+impl SqliteStore {
+    /// Opens the database at the given path, creating it if it does not exist, and brings its
+    /// schema up to date.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The database file.
+    ///
+    /// # Errors
+    ///
+    /// - [`PersistenceError::Database`] if the database cannot be opened or migrated.
+    /// - [`PersistenceError::UnsupportedVersion`] if the database was written by a newer version of
+    ///   this library.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, PersistenceError> {
+        Self::migrate(Connection::open(path)?)
+    }
+
+    /// Opens a fresh database that lives only in memory.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`PersistenceError::Database`] if the database cannot be created.
+    pub fn open_in_memory() -> Result<Self, PersistenceError> {
+        Self::migrate(Connection::open_in_memory()?)
+    }
+
+    /// Returns the schema version of the database.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`PersistenceError::Database`] if the version cannot be read.
+    pub fn schema_version(&self) -> Result<u32, PersistenceError> {
+        Ok(self
+            .connection
+            .query_row("PRAGMA user_version", [], |row| row.get(0))?)
+    }
+
+    /// Applies every migration the database has not seen yet, in a single transaction.
+    fn migrate(mut connection: Connection) -> Result<Self, PersistenceError> {
+        let version: u32 = connection.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+        if version > SCHEMA_VERSION {
+            return Err(PersistenceError::UnsupportedVersion(version));
+        }
+
+        let transaction = connection.transaction()?;
+        for migration in MIGRATIONS.iter().skip(version as usize) {
+            transaction.execute_batch(migration)?;
+        }
+        transaction.pragma_update(None, "user_version", SCHEMA_VERSION)?;
+        transaction.commit()?;
+
+        Ok(Self { connection })
+    }
+
+    /// Inserts or replaces matchboxes within a transaction.
+    fn write_matchboxes(
+        transaction: &Transaction<'_>,
+        matchboxes: &[(u16, Matchbox)],
+    ) -> Result<(), PersistenceError> {
+        let codec = BoardCodec::new();
+        let mut statement = transaction
+            .prepare("INSERT OR REPLACE INTO matchboxes (key, board, beads) VALUES (?1, ?2, ?3)")?;
+        for (key, matchbox) in matchboxes {
+            let board = codec
+                .from_number(*key)
+                .map_err(|_| PersistenceError::InvalidMatchbox(key.to_string()))?;
+            let beads = serde_json::to_string(&matchbox.beads())?;
+            statement.execute(params![key, codec.encode(&board), beads])?;
+        }
+        Ok(())
+    }
+}
+
+impl MatchboxStore for SqliteStore {
+    fn config(&self) -> Result<Option<MenaceConfig>, PersistenceError> {
+        let config: Option<String> =
+            self.connection
+                .query_row("SELECT config FROM menace WHERE id = 1", [], |row| {
+                    row.get(0)
+                })?;
+        config
+            .map(|json| serde_json::from_str(&json).map_err(PersistenceError::from))
+            .transpose()
+    }
+
+    fn games_played(&self) -> Result<u64, PersistenceError> {
+        Ok(self.connection.query_row(
+            "SELECT games_played FROM menace WHERE id = 1",
+            [],
+            |row| row.get(0),
+        )?)
+    }
+
+    fn matchbox(&self, key: u16) -> Result<Option<Matchbox>, PersistenceError> {
+        let beads: Option<String> = self
+            .connection
+            .query_row(
+                "SELECT beads FROM matchboxes WHERE key = ?1",
+                [key],
+                |row| row.get(0),
+            )
+            .optional()?;
+        beads
+            .map(|json| Ok(Matchbox::from_beads(serde_json::from_str(&json)?)))
+            .transpose()
+    }
+
+    fn matchboxes(&self) -> Result<Vec<(u16, Matchbox)>, PersistenceError> {
+        let mut statement = self
+            .connection
+            .prepare("SELECT key, beads FROM matchboxes ORDER BY key")?;
+        let rows = statement.query_map([], |row| {
+            Ok((row.get::<_, u16>(0)?, row.get::<_, String>(1)?))
+        })?;
+
+        let mut matchboxes = Vec::new();
+        for row in rows {
+            let (key, json) = row?;
+            matchboxes.push((key, Matchbox::from_beads(serde_json::from_str(&json)?)));
+        }
+        Ok(matchboxes)
+    }
+
+    fn save(&mut self, menace: &Menace) -> Result<(), PersistenceError> {
+        let config = serde_json::to_string(menace.config())?;
+        let matchboxes: Vec<(u16, Matchbox)> = menace
+            .boxes()
+            .map(|(key, &matchbox)| (key, matchbox))
+            .collect();
+
+        let transaction = self.connection.transaction()?;
+        transaction.execute(
+            "UPDATE menace SET config = ?1, games_played = ?2 WHERE id = 1",
+            params![config, menace.games_played()],
+        )?;
+        transaction.execute("DELETE FROM matchboxes", [])?;
+        Self::write_matchboxes(&transaction, &matchboxes)?;
+        transaction.commit()?;
+        Ok(())
+    }
+
+    fn commit_game(
+        &mut self,
+        games_played: u64,
+        matchboxes: &[(u16, Matchbox)],
+    ) -> Result<(), PersistenceError> {
+        let transaction = self.connection.transaction()?;
+        transaction.execute(
+            "UPDATE menace SET games_played = ?1 WHERE id = 1",
+            [games_played],
+        )?;
+        Self::write_matchboxes(&transaction, matchboxes)?;
+        transaction.commit()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use rand::SeedableRng;
+
+    use super::*;
+    use crate::{
+        play_match,
+        EmptyBoxPolicy,
+        Flavor,
+        MenaceAgent,
+        RandomAgent,
+        Seeded,
+        SeededRng,
+    };
+
+    fn temp_path(name: &str) -> std::path::PathBuf {
+        std::env::temp_dir().join(format!("{name}-{}.sqlite", std::process::id()))
+    }
+
+    fn trained() -> Menace {
+        let mut learner = MenaceAgent::new(Menace::with_config(MenaceConfig {
+            flavor: Flavor::Symmetric,
+            empty_box: EmptyBoxPolicy::Refill,
+            ..MenaceConfig::default()
+        }))
+        .with_seed(1);
+        let mut opponent = RandomAgent::new().with_seed(2);
+        for _ in 0..30 {
+            play_match(&mut learner, &mut opponent).unwrap();
+        }
+        learner.into_menace()
+    }
+
+    #[test]
+    fn test_new_database_is_empty() {
+        let store = SqliteStore::open_in_memory().unwrap();
+        assert_eq!(store.schema_version().unwrap(), SCHEMA_VERSION);
+        assert_eq!(store.config().unwrap(), None);
+        assert_eq!(store.games_played().unwrap(), 0);
+        assert!(store.matchboxes().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_save_and_load() {
+        let menace = trained();
+        let mut store = SqliteStore::open_in_memory().unwrap();
+        menace.save_to(&mut store).unwrap();
+        assert_eq!(Menace::from_store(&store).unwrap(), menace);
+    }
+
+    #[test]
+    fn test_reinforce_into_survives_reopening() {
+        let path = temp_path("menace-store");
+        let _ = std::fs::remove_file(&path);
+        let mut rng = SeededRng::seed_from_u64(3);
+        let mut menace = trained();
+        {
+            let mut store = SqliteStore::open(&path).unwrap();
+            menace.save_to(&mut store).unwrap();
+            let board = crate::Board::new();
+            menace.choose_move(&board, &mut rng).unwrap();
+            menace
+                .reinforce_into(crate::GameResult::Win, &mut store)
+                .unwrap();
+        }
+
+        let store = SqliteStore::open(&path).unwrap();
+        let loaded = Menace::from_store(&store);
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(loaded.unwrap(), menace);
+    }
+
+    #[test]
+    fn test_rejects_newer_schema() {
+        let path = temp_path("menace-newer");
+        let _ = std::fs::remove_file(&path);
+        {
+            let connection = Connection::open(&path).unwrap();
+            connection
+                .pragma_update(None, "user_version", SCHEMA_VERSION + 1)
+                .unwrap();
+        }
+        let result = SqliteStore::open(&path);
+        std::fs::remove_file(&path).unwrap();
+        assert!(matches!(
+            result,
+            Err(PersistenceError::UnsupportedVersion(version)) if version == SCHEMA_VERSION + 1
+        ));
+    }
+
+    #[test]
+    fn test_failed_commit_changes_nothing() {
+        let mut store = SqliteStore::open_in_memory().unwrap();
+        let matchbox = Matchbox::from_beads([1; 9]);
+        // 19683 is not a board number, so the second write fails and the game is rolled back.
+        let result = store.commit_game(5, &[(0, matchbox), (19_683, matchbox)]);
+        assert_eq!(
+            result,
+            Err(PersistenceError::InvalidMatchbox("19683".to_string()))
+        );
+        assert_eq!(store.games_played().unwrap(), 0);
+        assert_eq!(store.matchbox(0).unwrap(), None);
+    }
+}
@@ -0,0 +1,149 @@
+// SPDX-FileCopyrightText: 2022 - 2024 Ali Sajid Imami
+//
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use std::fmt;
+
+use crate::Coord;
+
+/// One of the eight symmetries of the square board (the dihedral group D4).
+///
+/// MENACE-C treats boards that are rotations or reflections of each other as the same position.
+/// A `Transform` maps a square to its image under one of those symmetries, and can be applied to
+/// a whole board with [`Board::transform`](crate::Board::transform).
+///
+/// Rotations are clockwise.
+///
+/// # Examples
+///
+/// ```
+/// use tictacrustle::{
+///     Coord,
+///     Transform,
+/// };
+///
+/// let top_left = Coord::new(1, 1).unwrap();
+/// let top_right = Coord::new(1, 3).unwrap();
+///
+/// assert_eq!(top_left.transform(Transform::Rotate90), top_right);
+/// assert_eq!(top_right.transform(Transform::Rotate90.inverse()), top_left);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Transform {
+    /// Leaves the board unchanged.
+    Identity,
+    /// Rotates the board a quarter turn clockwise.
+    Rotate90,
+    /// Rotates the board a half turn.
+    Rotate180,
+    /// Rotates the board three quarter turns clockwise.
+    Rotate270,
+    /// Mirrors the board left to right.
+    FlipHorizontal,
+    /// Mirrors the board top to bottom.
+    FlipVertical,
+    /// Mirrors the board across the main diagonal, swapping rows and columns.
+    Transpose,
+    /// Mirrors the board across the anti-diagonal.
+    AntiTranspose,
+}
+
+impl Transform {
+    /// All eight symmetries, starting with [`Transform::Identity`].
+    pub const ALL: [Self; 8] = [
+        Self::Identity,
+        Self::Rotate90,
+        Self::Rotate180,
+        Self::Rotate270,
+        Self::FlipHorizontal,
+        Self::FlipVertical,
+        Self::Transpose,
+        Self::AntiTranspose,
+    ];
+
+    /// Returns the transform that undoes this one.
+    ///
+    /// Every reflection is its own inverse; the quarter turns undo each other.
+    #[must_use]
+    pub const fn inverse(self) -> Self {
+        match self {
+            Self::Rotate90 => Self::Rotate270,
+            Self::Rotate270 => Self::Rotate90,
+            other => other,
+        }
+    }
+
+    /// Maps a square to its image under the transform.
+    ///
+    /// # Arguments
+    ///
+    /// * `coord` - The square to transform.
+    ///
+    /// # Returns
+    ///
+    /// The position the square moves to.
+    #[must_use]
+    pub const fn apply(self, coord: Coord) -> Coord {
+        coord.transform(self)
+    }
+}
+
+impl fmt::Display for Transform {
+    /// Formats the transform as a short human readable name.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Identity => write!(f, "identity"),
+            Self::Rotate90 => write!(f, "rotate 90"),
+            Self::Rotate180 => write!(f, "rotate 180"),
+            Self::Rotate270 => write!(f, "rotate 270"),
+            Self::FlipHorizontal => write!(f, "flip horizontal"),
+            Self::FlipVertical => write!(f, "flip vertical"),
+            Self::Transpose => write!(f, "transpose"),
+            Self::AntiTranspose => write!(f, "anti-transpose"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::*;
+
+    #[test]
+    fn test_inverse_undoes_transform() {
+        for transform in Transform::ALL {
+            for coord in Coord::all() {
+                assert_eq!(transform.inverse().apply(transform.apply(coord)), coord);
+            }
+        }
+    }
+
+    #[test]
+    fn test_transforms_are_permutations() {
+        for transform in Transform::ALL {
+            let images: HashSet<Coord> = Coord::all().map(|coord| transform.apply(coord)).collect();
+            assert_eq!(images.len(), 9);
+        }
+    }
+
+    #[test]
+    fn test_transforms_are_distinct() {
+        let corner = Coord::new(1, 1).unwrap();
+        let edge = Coord::new(1, 2).unwrap();
+        let images: HashSet<(Coord, Coord)> = Transform::ALL
+            .iter()
+            .map(|transform| (transform.apply(corner), transform.apply(edge)))
+            .collect();
+        assert_eq!(images.len(), 8);
+    }
+
+    #[test]
+    fn test_centre_is_fixed() {
+        let centre = Coord::new(2, 2).unwrap();
+        for transform in Transform::ALL {
+            assert_eq!(transform.apply(centre), centre);
+        }
+    }
+}
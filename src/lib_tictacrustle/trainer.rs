@@ -0,0 +1,577 @@
+// SPDX-FileCopyrightText: 2022 - 2024 Ali Sajid Imami
+//
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use std::collections::{
+    HashSet,
+    VecDeque,
+};
+
+use log::info;
+use rand::{
+    Rng,
+    SeedableRng,
+};
+
+use crate::{
+    play_match,
+    Agent,
+    GameError,
+    GameResult,
+    MatchRecord,
+    MenaceAgent,
+    Seeded,
+    SeededRng,
+    Symbol,
+};
+
+/// Decides which side moves first in each training game.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum FirstPlayer {
+    /// The learner always plays `X`.
+    #[default]
+    Learner,
+    /// The opponent always plays `X`.
+    Opponent,
+    /// The learner plays `X` in the first game, and the sides swap after every game.
+    Alternate,
+    /// The side that plays `X` is picked at random for every game.
+    Random,
+}
+
+/// Win, draw and loss counts from the learner's point of view.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct ResultCounts {
+    /// The number of games the learner won.
+    pub wins:   u64,
+    /// The number of games that were drawn.
+    pub draws:  u64,
+    /// The number of games the learner lost.
+    pub losses: u64,
+}
+
+impl ResultCounts {
+    /// Counts one more game with the given result.
+    pub const fn record(&mut self, result: GameResult) {
+        match result {
+            GameResult::Win => self.wins += 1,
+            GameResult::Draw => self.draws += 1,
+            GameResult::Loss => self.losses += 1,
+        }
+    }
+
+    /// Returns the number of games counted.
+    #[must_use]
+    pub const fn total(&self) -> u64 {
+        self.wins + self.draws + self.losses
+    }
+
+    /// Returns the fraction of games with the given result, or zero if no games were counted.
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn rate(&self, result: GameResult) -> f64 {
+        let count = match result {
+            GameResult::Win => self.wins,
+            GameResult::Draw => self.draws,
+            GameResult::Loss => self.losses,
+        };
+        if self.total() == 0 {
+            0.0
+        } else {
+            count as f64 / self.total() as f64
+        }
+    }
+}
+
+/// A condition that ends training early, judged over the most recent games.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum StopCondition {
+    /// Stop once the fraction of drawn games reaches `rate`.
+    DrawRate {
+        /// The number of recent games considered. The condition is never met before this many
+        /// games have been played.
+        window: usize,
+        /// The fraction of games, between 0 and 1, that must be drawn.
+        rate:   f64,
+    },
+    /// Stop once the fraction of games the learner did not lose reaches `rate`.
+    UnbeatenRate {
+        /// The number of recent games considered. The condition is never met before this many
+        /// games have been played.
+        window: usize,
+        /// The fraction of games, between 0 and 1, that must be won or drawn.
+        rate:   f64,
+    },
+}
+
+impl StopCondition {
+    /// Returns the number of recent games the condition is judged over.
+    #[must_use]
+    pub const fn window(&self) -> usize {
+        match *self {
+            Self::DrawRate { window, .. } | Self::UnbeatenRate { window, .. } => window,
+        }
+    }
+
+    /// Checks whether the condition is met by the most recent results.
+    ///
+    /// # Arguments
+    ///
+    /// * `recent` - The results of the most recent games, holding at most [`StopCondition::window`]
+    ///   entries.
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn is_met(&self, recent: &VecDeque<GameResult>) -> bool {
+        if recent.len() < self.window() || recent.is_empty() {
+            return false;
+        }
+        let (matching, rate) = match *self {
+            Self::DrawRate { rate, .. } => (
+                recent
+                    .iter()
+                    .filter(|&&result| result == GameResult::Draw)
+                    .count(),
+                rate,
+            ),
+            Self::UnbeatenRate { rate, .. } => (
+                recent
+                    .iter()
+                    .filter(|&&result| result != GameResult::Loss)
+                    .count(),
+                rate,
+            ),
+        };
+        matching as f64 / recent.len() as f64 >= rate
+    }
+}
+
+/// The settings of a [`Trainer`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TrainerConfig {
+    /// The maximum number of games to play.
+    pub games:        u64,
+    /// Which side moves first in each game.
+    pub first_player: FirstPlayer,
+    /// How often progress is logged, in games, or zero to never log.
+    pub report_every: u64,
+    /// A condition that ends training early, if any.
+    pub stop:         Option<StopCondition>,
+}
+
+impl Default for TrainerConfig {
+    /// Creates a configuration for 10 000 games with alternating first moves, logging every
+    /// 1000 games and stopping once 99% of the last 1000 games are drawn.
+    fn default() -> Self {
+        Self {
+            games:        10_000,
+            first_player: FirstPlayer::Alternate,
+            report_every: 1_000,
+            stop:         Some(StopCondition::DrawRate {
+                window: 1_000,
+                rate:   0.99,
+            }),
+        }
+    }
+}
+
+/// What a learning agent has learned so far, as included in the progress reports of a
+/// [`Trainer`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LearnerStats {
+    /// The number of positions the agent has learned about, such as MENACE's matchboxes.
+    pub entries: usize,
+    /// The number of beads in all of MENACE's matchboxes, or `None` if the agent has no beads.
+    pub beads:   Option<u64>,
+}
+
+/// A learning agent that can describe what it has learned, so that a [`Trainer`] can report on
+/// it.
+///
+/// Both methods have defaults that report nothing, so an agent can opt in with an empty `impl`.
+pub trait Reportable {
+    /// Returns what the agent has learned so far, or `None` if it does not keep count.
+    fn stats(&self) -> Option<LearnerStats> {
+        None
+    }
+
+    /// Returns the [`BoardCodec::to_number`](crate::BoardCodec::to_number) keys of the positions
+    /// the agent drew on while playing a game.
+    ///
+    /// # Arguments
+    ///
+    /// * `record` - The game the agent played.
+    /// * `symbol` - The symbol the agent played with.
+    fn positions_used(&self, _record: &MatchRecord, _symbol: Symbol) -> Vec<u16> {
+        Vec::new()
+    }
+}
+
+/// The progress of a training run, as logged every [`TrainerConfig::report_every`] games.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TrainingReport {
+    /// The number of games played so far.
+    pub games:         u64,
+    /// The results since the previous report.
+    pub results:       ResultCounts,
+    /// The number of beads in all of MENACE's matchboxes, or `None` if the learner has no
+    /// beads.
+    pub beads:         Option<u64>,
+    /// The number of positions the learner drew on since the previous report, or `None` if the
+    /// learner does not report its [`LearnerStats`].
+    pub boxes_touched: Option<usize>,
+    /// The number of positions the learner has learned about in total, or `None` if the learner
+    /// does not report its [`LearnerStats`].
+    pub boxes:         Option<usize>,
+}
+
+/// The outcome of a call to [`Trainer::run`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TrainingSummary {
+    /// The number of games played.
+    pub games:   u64,
+    /// The results of every game played.
+    pub results: ResultCounts,
+    /// Whether training ended because the [`StopCondition`] was met.
+    pub stopped: bool,
+}
+
+/// Trains a learning agent, MENACE by default, by playing it against an opponent.
+///
+/// Each game is played with [`play_match`], which lets the learner update itself as soon as the
+/// game is over. Progress is logged through the [`log`] crate at the `info` level, along with
+/// the statistics the learner reports through [`Reportable`].
+///
+/// # Examples
+///
+/// ```
+/// use tictacrustle::{
+///     HeuristicAgent,
+///     Menace,
+///     MenaceAgent,
+///     Trainer,
+///     TrainerConfig,
+/// };
+///
+/// let mut trainer = Trainer::new(
+///     MenaceAgent::new(Menace::new()),
+///     Box::new(HeuristicAgent::new()),
+///     TrainerConfig {
+///         games: 100,
+///         stop: None,
+///         ..TrainerConfig::default()
+///     },
+/// );
+///
+/// let summary = trainer.run().unwrap();
+/// assert_eq!(summary.games, 100);
+/// assert_eq!(trainer.learner().menace().games_played(), 100);
+/// ```
+pub struct Trainer<L: Agent + Reportable = MenaceAgent> {
+    /// The settings of the training run.
+    config:   TrainerConfig,
+    /// The agent being trained.
+    learner:  L,
+    /// The agent the learner plays against.
+    opponent: Box<dyn Agent>,
+    /// The random number generator used to pick the first player.
+    rng:      SeededRng,
+}
+
+impl<L: Agent + Reportable> Trainer<L> {
+    /// Creates a new `Trainer`, seeded from the operating system.
+    ///
+    /// Seeding the learner, the opponent and the trainer with [`Seeded::with_seed`] makes a
+    /// training run reproducible.
+    ///
+    /// # Arguments
+    ///
+    /// * `learner` - The agent to train.
+    /// * `opponent` - The agent the learner plays against.
+    /// * `config` - The settings of the training run.
+    #[must_use]
+    pub fn new(learner: L, opponent: Box<dyn Agent>, config: TrainerConfig) -> Self {
+        Self {
+            config,
+            learner,
+            opponent,
+            rng: SeededRng::from_os_rng(),
+        }
+    }
+
+    /// Returns the settings of the training run.
+    #[must_use]
+    pub const fn config(&self) -> &TrainerConfig {
+        &self.config
+    }
+
+    /// Returns the agent being trained.
+    #[must_use]
+    pub const fn learner(&self) -> &L {
+        &self.learner
+    }
+
+    /// Consumes the trainer and returns the agent being trained.
+    #[must_use]
+    pub fn into_learner(self) -> L {
+        self.learner
+    }
+
+    /// Plays training games until [`TrainerConfig::games`] have been played or the
+    /// [`StopCondition`] is met.
+    ///
+    /// # Errors
+    ///
+    /// Returns the [`GameError`] raised by [`play_match`] if either agent plays an illegal move.
+    pub fn run(&mut self) -> Result<TrainingSummary, GameError> {
+        let mut summary = TrainingSummary::default();
+        let mut report = TrainingReport::default();
+        let mut touched = HashSet::new();
+        let window = self.config.stop.map_or(0, |stop| stop.window());
+        let mut recent = VecDeque::with_capacity(window);
+
+        while summary.games < self.config.games {
+            let learner_symbol = self.learner_symbol(summary.games);
+            let record = match learner_symbol {
+                Symbol::X => play_match(&mut self.learner, self.opponent.as_mut())?,
+                Symbol::O => play_match(self.opponent.as_mut(), &mut self.learner)?,
+            };
+            let result = record.outcome.result_for(learner_symbol);
+
+            summary.games += 1;
+            summary.results.record(result);
+            report.results.record(result);
+            touched.extend(self.learner.positions_used(&record, learner_symbol));
+
+            if window > 0 {
+                if recent.len() == window {
+                    recent.pop_front();
+                }
+                recent.push_back(result);
+            }
+
+            if self.config.report_every > 0 && summary.games % self.config.report_every == 0 {
+                report.games = summary.games;
+                if let Some(stats) = self.learner.stats() {
+                    report.beads = stats.beads;
+                    report.boxes_touched = Some(touched.len());
+                    report.boxes = Some(stats.entries);
+                }
+                log_report(&report);
+                report = TrainingReport::default();
+                touched.clear();
+            }
+
+            if self.config.stop.is_some_and(|stop| stop.is_met(&recent)) {
+                summary.stopped = true;
+                info!(
+                    "Stopping after {} games: stop condition {:?} met",
+                    summary.games, self.config.stop
+                );
+                break;
+            }
+        }
+
+        Ok(summary)
+    }
+
+    /// Decides which symbol the learner plays in a game.
+    fn learner_symbol(&mut self, game: u64) -> Symbol {
+        match self.config.first_player {
+            FirstPlayer::Learner => Symbol::X,
+            FirstPlayer::Opponent => Symbol::O,
+            FirstPlayer::Alternate if game % 2 == 1 => Symbol::O,
+            FirstPlayer::Alternate => Symbol::X,
+            FirstPlayer::Random if self.rng.random_bool(0.5) => Symbol::X,
+            FirstPlayer::Random => Symbol::O,
+        }
+    }
+}
+
+impl<L: Agent + Reportable> Seeded for Trainer<L> {
+    /// Returns the random number generator used for [`FirstPlayer::Random`].
+    fn rng_mut(&mut self) -> &mut SeededRng {
+        &mut self.rng
+    }
+}
+
+/// Logs a progress report at the `info` level.
+fn log_report(report: &TrainingReport) {
+    let matchboxes = match (report.beads, report.boxes_touched, report.boxes) {
+        (Some(beads), Some(touched), Some(boxes)) => {
+            format!("; {beads} beads; {touched} of {boxes} boxes touched")
+        }
+        (None, Some(touched), Some(boxes)) => format!("; {touched} of {boxes} positions touched"),
+        _ => String::new(),
+    };
+    info!(
+        "After {} games: win {:.1}%, draw {:.1}%, loss {:.1}%{matchboxes}",
+        report.games,
+        report.results.rate(GameResult::Win) * 100.0,
+        report.results.rate(GameResult::Draw) * 100.0,
+        report.results.rate(GameResult::Loss) * 100.0,
+    );
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use std::sync::Arc;
+
+    use rstest::rstest;
+
+    use super::*;
+    use crate::{
+        Flavor,
+        LearningConfig,
+        Menace,
+        PerfectAgent,
+        QLearningAgent,
+        RandomAgent,
+        ScriptedAgent,
+        Solver,
+    };
+
+    #[rstest]
+    #[case(FirstPlayer::Learner, [Symbol::X, Symbol::X, Symbol::X])]
+    #[case(FirstPlayer::Opponent, [Symbol::O, Symbol::O, Symbol::O])]
+    #[case(FirstPlayer::Alternate, [Symbol::X, Symbol::O, Symbol::X])]
+    fn test_learner_symbol(#[case] first_player: FirstPlayer, #[case] expected: [Symbol; 3]) {
+        let mut trainer = Trainer::new(
+            MenaceAgent::new(Menace::new()).with_seed(1),
+            Box::new(ScriptedAgent::new(Vec::new())),
+            TrainerConfig {
+                first_player,
+                ..TrainerConfig::default()
+            },
+        );
+        let symbols: Vec<Symbol> = (0..3).map(|game| trainer.learner_symbol(game)).collect();
+        assert_eq!(symbols, expected);
+    }
+
+    #[test]
+    fn test_counts_results() {
+        // The opponent resigns at once, so the learner wins every game.
+        let mut trainer = Trainer::new(
+            MenaceAgent::new(Menace::new()).with_seed(1),
+            Box::new(ScriptedAgent::new(Vec::new())),
+            TrainerConfig {
+                games:        20,
+                first_player: FirstPlayer::Learner,
+                report_every: 5,
+                stop:         None,
+            },
+        );
+        let summary = trainer.run().unwrap();
+        assert_eq!(summary.games, 20);
+        assert_eq!(summary.results.wins, 20);
+        assert!(!summary.stopped);
+        assert!((summary.results.rate(GameResult::Win) - 1.0).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn test_stop_condition_ends_training() {
+        // The opponent resigns before MENACE moves, so every game is a win.
+        let mut trainer = Trainer::new(
+            MenaceAgent::new(Menace::new()).with_seed(1),
+            Box::new(ScriptedAgent::new(Vec::new())),
+            TrainerConfig {
+                games:        1_000,
+                first_player: FirstPlayer::Opponent,
+                report_every: 0,
+                stop:         Some(StopCondition::UnbeatenRate {
+                    window: 10,
+                    rate:   1.0,
+                }),
+            },
+        );
+        let summary = trainer.run().unwrap();
+        assert_eq!(summary.games, 10);
+        assert!(summary.stopped);
+        assert!(trainer.learner().menace().is_empty());
+    }
+
+    #[test]
+    fn test_trains_any_learning_agent() {
+        let mut trainer = Trainer::new(
+            QLearningAgent::new(LearningConfig::default()).with_seed(1),
+            Box::new(RandomAgent::new().with_seed(2)),
+            TrainerConfig {
+                games:        200,
+                first_player: FirstPlayer::Alternate,
+                report_every: 50,
+                stop:         None,
+            },
+        );
+        let summary = trainer.run().unwrap();
+        assert_eq!(summary.games, 200);
+        assert_eq!(summary.results.total(), 200);
+        assert_eq!(trainer.learner().games_played(), 200);
+        assert!(!trainer.learner().is_empty());
+        assert_eq!(trainer.learner().stats(), None);
+    }
+
+    #[rstest]
+    #[case(StopCondition::DrawRate { window: 4, rate: 0.75 }, &[GameResult::Draw, GameResult::Draw, GameResult::Win, GameResult::Draw], true)]
+    #[case(StopCondition::DrawRate { window: 4, rate: 0.75 }, &[GameResult::Draw, GameResult::Draw, GameResult::Draw], false)]
+    #[case(StopCondition::DrawRate { window: 4, rate: 0.75 }, &[GameResult::Draw, GameResult::Loss, GameResult::Win, GameResult::Draw], false)]
+    #[case(StopCondition::UnbeatenRate { window: 2, rate: 1.0 }, &[GameResult::Win, GameResult::Draw], true)]
+    #[case(StopCondition::UnbeatenRate { window: 2, rate: 1.0 }, &[GameResult::Win, GameResult::Loss], false)]
+    fn test_stop_condition(
+        #[case] condition: StopCondition,
+        #[case] results: &[GameResult],
+        #[case] expected: bool,
+    ) {
+        let recent: VecDeque<GameResult> = results.iter().copied().collect();
+        assert_eq!(condition.is_met(&recent), expected);
+    }
+
+    #[test]
+    fn test_menace_learns_against_perfect_play() {
+        let mut trainer = Trainer::new(
+            MenaceAgent::new(Menace::new()).with_seed(1),
+            Box::new(
+                PerfectAgent::with_solver(Arc::new(Solver::new()))
+                    .with_rng(SeededRng::seed_from_u64(2)),
+            ),
+            TrainerConfig {
+                games:        500,
+                first_player: FirstPlayer::Alternate,
+                report_every: 0,
+                stop:         None,
+            },
+        );
+        // Each run plays another window of games, so the windows can be compared.
+        let windows: Vec<TrainingSummary> = (0..4).map(|_| trainer.run().unwrap()).collect();
+        assert!(windows.iter().all(|window| window.results.wins == 0));
+
+        let first = windows[0].results.rate(GameResult::Draw);
+        let last = windows[3].results.rate(GameResult::Draw);
+        assert!(last > first, "draw rate fell from {first} to {last}");
+        assert_eq!(trainer.learner().menace().games_played(), 2_000);
+    }
+
+    #[test]
+    fn test_seeded_runs_are_reproducible() {
+        let run = || {
+            let mut trainer = Trainer::new(
+                MenaceAgent::new(Menace::with_flavor(Flavor::Symmetric)).with_seed(7),
+                Box::new(RandomAgent::new().with_seed(8)),
+                TrainerConfig {
+                    games:        300,
+                    first_player: FirstPlayer::Random,
+                    report_every: 0,
+                    stop:         None,
+                },
+            )
+            .with_rng(SeededRng::seed_from_u64(9));
+            let summary = trainer.run().unwrap();
+            (summary, trainer.into_learner().into_menace())
+        };
+
+        let (first_summary, first_menace) = run();
+        let (second_summary, second_menace) = run();
+        assert_eq!(first_summary, second_summary);
+        assert_eq!(first_menace, second_menace);
+    }
+}
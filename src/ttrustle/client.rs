@@ -0,0 +1,225 @@
+// SPDX-FileCopyrightText: 2022 - 2024 Ali Sajid Imami
+//
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use std::fmt;
+
+use serde::{
+    de::DeserializeOwned,
+    Serialize,
+};
+use tictacrustle::{
+    Coord,
+    ErrorResponse,
+    GameView,
+    JoinRequest,
+    LobbyEntry,
+    MatchMove,
+    MatchView,
+    MoveResponse,
+    NewGameRequest,
+    OutcomeView,
+    PlayerTicket,
+};
+
+/// An error talking to a `ttserver`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ClientError {
+    /// The server refused the move because the game does not allow it: the square is taken, it
+    /// is not the player's turn, or the game is over or full.
+    IllegalMove(String),
+    /// The server could not make sense of the request.
+    BadRequest(String),
+    /// The server refused the request because it does not carry the player's token.
+    Forbidden(String),
+    /// The server no longer knows the game, usually because it expired.
+    GameExpired(String),
+    /// The server answered with an unexpected error.
+    Server(u16, String),
+    /// The server could not be reached, or sent something that is not a valid answer.
+    Transport(String),
+}
+
+impl ClientError {
+    /// Classifies an error response by its HTTP status.
+    fn from_status(status: u16, message: String) -> Self {
+        match status {
+            400 => Self::BadRequest(message),
+            403 => Self::Forbidden(message),
+            409 => Self::IllegalMove(message),
+            404 | 410 => Self::GameExpired(message),
+            _ => Self::Server(status, message),
+        }
+    }
+}
+
+impl fmt::Display for ClientError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::IllegalMove(message) => write!(f, "Illegal move: {message}"),
+            Self::BadRequest(message) => write!(f, "Bad request: {message}"),
+            Self::Forbidden(message) => write!(f, "Forbidden: {message}"),
+            Self::GameExpired(message) => write!(f, "The game has expired: {message}"),
+            Self::Server(status, message) => write!(f, "Server error {status}: {message}"),
+            Self::Transport(message) => write!(f, "Could not reach the server: {message}"),
+        }
+    }
+}
+
+impl std::error::Error for ClientError {}
+
+impl From<ureq::Error> for ClientError {
+    fn from(error: ureq::Error) -> Self {
+        match error {
+            ureq::Error::Status(status, response) => {
+                let message = response
+                    .into_json::<ErrorResponse>()
+                    .map_or_else(|err| err.to_string(), |body| body.error);
+                Self::from_status(status, message)
+            }
+            ureq::Error::Transport(transport) => Self::Transport(transport.to_string()),
+        }
+    }
+}
+
+/// A client for the `ttserver` HTTP API.
+#[derive(Debug, Clone)]
+pub struct Client {
+    /// The base URL of the server, without a trailing slash.
+    base_url: String,
+    /// The HTTP agent, which reuses connections between requests.
+    agent:    ureq::Agent,
+}
+
+impl Client {
+    /// Creates a client for the server at the given URL, such as `http://localhost:8080`.
+    pub fn new(base_url: &str) -> Self {
+        Self {
+            base_url: base_url.trim_end_matches('/').to_string(),
+            agent:    ureq::Agent::new(),
+        }
+    }
+
+    /// Starts a game against MENACE.
+    pub fn new_game(&self, menace_first: bool) -> Result<GameView, ClientError> {
+        self.post("/games", &NewGameRequest { menace_first })
+    }
+
+    /// Plays a move and returns MENACE's reply.
+    pub fn play(&self, id: &str, coord: Coord) -> Result<MoveResponse, ClientError> {
+        self.post(&format!("/games/{id}/moves"), &coord)
+    }
+
+    /// Fetches the outcome of a finished game.
+    pub fn outcome(&self, id: &str) -> Result<OutcomeView, ClientError> {
+        self.get(&format!("/games/{id}/outcome"))
+    }
+
+    /// Lists the games in the lobby waiting for a second player, oldest first.
+    pub fn lobby(&self) -> Result<Vec<LobbyEntry>, ClientError> {
+        self.get("/lobby")
+    }
+
+    /// Opens a game in the lobby, playing `X`.
+    pub fn open_match(&self, name: Option<String>) -> Result<PlayerTicket, ClientError> {
+        self.post("/lobby", &JoinRequest { name })
+    }
+
+    /// Joins a game in the lobby, playing `O`.
+    pub fn join_match(&self, id: &str, name: Option<String>) -> Result<PlayerTicket, ClientError> {
+        self.post(&format!("/lobby/{id}/join"), &JoinRequest { name })
+    }
+
+    /// Fetches the current state of a game between humans.
+    pub fn match_view(&self, id: &str) -> Result<MatchView, ClientError> {
+        self.get(&format!("/lobby/{id}"))
+    }
+
+    /// Plays a move in a game between humans.
+    pub fn play_match(
+        &self,
+        ticket: &PlayerTicket,
+        coord: Coord,
+    ) -> Result<MatchView, ClientError> {
+        let request = MatchMove {
+            token: ticket.token.clone(),
+            coord,
+        };
+        self.post(&format!("/lobby/{}/moves", ticket.id), &request)
+    }
+
+    /// Sends a GET request and parses the JSON answer.
+    fn get<T: DeserializeOwned>(&self, path: &str) -> Result<T, ClientError> {
+        let response = self.agent.get(&self.url(path)).call()?;
+        response
+            .into_json()
+            .map_err(|err| ClientError::Transport(err.to_string()))
+    }
+
+    /// Sends a POST request with a JSON body and parses the JSON answer.
+    fn post<B: Serialize, T: DeserializeOwned>(
+        &self,
+        path: &str,
+        body: &B,
+    ) -> Result<T, ClientError> {
+        let response = self.agent.post(&self.url(path)).send_json(body)?;
+        response
+            .into_json()
+            .map_err(|err| ClientError::Transport(err.to_string()))
+    }
+
+    /// Builds the full URL of an endpoint.
+    fn url(&self, path: &str) -> String {
+        format!("{}{path}", self.base_url)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_url() {
+        let client = Client::new("http://localhost:8080/");
+        assert_eq!(client.url("/games"), "http://localhost:8080/games");
+    }
+
+    #[test]
+    fn test_error_classification() {
+        let message = String::from("boom");
+        assert!(matches!(
+            ClientError::from_status(409, message.clone()),
+            ClientError::IllegalMove(_)
+        ));
+        assert!(matches!(
+            ClientError::from_status(400, message.clone()),
+            ClientError::BadRequest(_)
+        ));
+        assert!(matches!(
+            ClientError::from_status(403, message.clone()),
+            ClientError::Forbidden(_)
+        ));
+        assert!(matches!(
+            ClientError::from_status(410, message.clone()),
+            ClientError::GameExpired(_)
+        ));
+        assert!(matches!(
+            ClientError::from_status(404, message.clone()),
+            ClientError::GameExpired(_)
+        ));
+        assert_eq!(
+            ClientError::from_status(500, message),
+            ClientError::Server(500, "boom".to_string())
+        );
+    }
+
+    #[test]
+    fn test_unreachable_server() {
+        let client = Client::new("http://127.0.0.1:1");
+        assert!(matches!(
+            client.new_game(false),
+            Err(ClientError::Transport(_))
+        ));
+    }
+}
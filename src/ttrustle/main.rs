@@ -3,13 +3,94 @@
 // SPDX-License-Identifier: Apache-2.0
 // SPDX-License-Identifier: MIT
 
+#[cfg(feature = "client")]
+mod client;
+#[cfg(feature = "client")]
+mod remote;
+
+#[cfg(feature = "client")]
+use std::{
+    io,
+    time::Duration,
+};
+
+use anyhow::{
+    bail,
+    Context,
+    Result,
+};
 use tictacrustle::Board;
 
-fn main() {
+#[cfg(feature = "client")]
+use crate::client::Client;
+
+/// Describes the command-line arguments.
+const USAGE: &str = "Usage: ttrustle [--server URL [--menace-first | --lobby [--name NAME]]]";
+
+/// How often the server is asked for the game while the other human is thinking.
+#[cfg(feature = "client")]
+const LOBBY_POLL: Duration = Duration::from_secs(1);
+
+fn main() -> Result<()> {
+    let mut server = None;
+    let mut menace_first = false;
+    let mut lobby = false;
+    let mut name = None;
+
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--server" => server = Some(args.next().context("--server needs a URL")?),
+            "--menace-first" => menace_first = true,
+            "--lobby" => lobby = true,
+            "--name" => name = Some(args.next().context("--name needs a value")?),
+            _ => bail!("Unknown argument {arg}\n\n{USAGE}"),
+        }
+    }
+
+    if let Some(url) = server {
+        return play_remote(&url, menace_first, lobby, name);
+    }
+
     let mut board = Board::default();
     println!("{board}");
     board.get_square_mut(2, 2).set_x();
     println!("{board}");
     board.get_square_mut(1, 1).set_o();
     println!("{board}");
+    Ok(())
+}
+
+/// Plays against MENACE, or in the lobby, on the server at `url`.
+///
+/// # Arguments
+///
+/// * `url` - The base URL of the server.
+/// * `menace_first` - Whether MENACE makes the first move.
+/// * `lobby` - Whether to play another human in the lobby instead of MENACE.
+/// * `name` - The name to show in the lobby.
+#[cfg(feature = "client")]
+fn play_remote(url: &str, menace_first: bool, lobby: bool, name: Option<String>) -> Result<()> {
+    let client = Client::new(url);
+    if lobby {
+        return remote::play_lobby(
+            &client,
+            name,
+            LOBBY_POLL,
+            &mut io::stdin().lock(),
+            &mut io::stdout(),
+        );
+    }
+    remote::play(
+        &client,
+        menace_first,
+        &mut io::stdin().lock(),
+        &mut io::stdout(),
+    )
+}
+
+/// Refuses to play on a server, since the client was left out of this build.
+#[cfg(not(feature = "client"))]
+fn play_remote(url: &str, _menace_first: bool, _lobby: bool, _name: Option<String>) -> Result<()> {
+    bail!("ttrustle was built without the client feature, so it cannot play on {url}")
 }
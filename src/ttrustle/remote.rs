@@ -0,0 +1,251 @@
+// SPDX-FileCopyrightText: 2022 - 2024 Ali Sajid Imami
+//
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use std::{
+    io::{
+        BufRead,
+        Write,
+    },
+    thread,
+    time::Duration,
+};
+
+use anyhow::{
+    bail,
+    Result,
+};
+use tictacrustle::{
+    Board,
+    Coord,
+    GameError,
+    GameResult,
+    GameState,
+    Symbol,
+};
+
+use crate::client::{
+    Client,
+    ClientError,
+};
+
+/// What the player typed at the move prompt.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Command {
+    /// Play the given square.
+    Move(Coord),
+    /// Leave the game.
+    Quit,
+}
+
+/// Parses a line typed at the move prompt.
+///
+/// A move is either a row and a column, such as `1 3`, or a single numeric keypad key, where
+/// `7 8 9` is the top row. `q` or `quit` leaves the game.
+pub fn parse_command(line: &str) -> Option<Command> {
+    let line = line.trim();
+    if line.eq_ignore_ascii_case("q") || line.eq_ignore_ascii_case("quit") {
+        return Some(Command::Quit);
+    }
+
+    let numbers: Vec<usize> = line
+        .split(|c: char| c.is_whitespace() || c == ',')
+        .filter(|part| !part.is_empty())
+        .map(str::parse)
+        .collect::<Result<_, _>>()
+        .ok()?;
+    let coord = match numbers.as_slice() {
+        [key] => Coord::from_keypad(u8::try_from(*key).ok()?).ok()?,
+        [row, col] => Coord::new(*row, *col).ok()?,
+        _ => return None,
+    };
+    Some(Command::Move(coord))
+}
+
+/// Plays one game against the MENACE hosted by a server, reading moves from `input` and
+/// writing the board to `output` after every exchange.
+///
+/// Illegal moves are reported and the player is asked again. If the server forgets the game,
+/// or cannot be reached, the game ends with an error.
+pub fn play<R: BufRead, W: Write>(
+    client: &Client,
+    menace_first: bool,
+    input: &mut R,
+    output: &mut W,
+) -> Result<()> {
+    let mut game = client.new_game(menace_first)?;
+    writeln!(
+        output,
+        "Game {} started. You play {}, MENACE plays {}.",
+        game.id, game.human, game.menace
+    )?;
+    if let Some(coord) = game.moves.first() {
+        writeln!(output, "MENACE played {coord}.")?;
+    }
+    render(game.decode_board(), output)?;
+
+    while !game.state.is_over() {
+        let Some(coord) = read_move(input, output)? else {
+            return Ok(());
+        };
+
+        match client.play(&game.id, coord) {
+            Ok(response) => {
+                if let Some(reply) = response.menace_move {
+                    writeln!(output, "MENACE played {reply}.")?;
+                }
+                game = response.game;
+                render(game.decode_board(), output)?;
+            }
+            Err(ClientError::IllegalMove(message)) => {
+                writeln!(output, "That move is not allowed: {message}")?;
+            }
+            Err(ClientError::GameExpired(message)) => {
+                bail!("The server no longer has game {}: {message}", game.id);
+            }
+            Err(err) => return Err(err.into()),
+        }
+    }
+
+    let outcome = client.outcome(&game.id)?;
+    let message = match outcome.result {
+        GameResult::Win => "You win!",
+        GameResult::Draw => "It's a draw.",
+        GameResult::Loss => "MENACE wins.",
+    };
+    writeln!(output, "{message}")?;
+    Ok(())
+}
+
+/// Plays one game against another human through the server's lobby, reading moves from `input`
+/// and writing the board to `output` whenever it changes.
+///
+/// The oldest game waiting in the lobby is joined, or a new one is opened if there is none.
+/// While it is the other player's turn, the server is asked for the game every `poll`.
+pub fn play_lobby<R: BufRead, W: Write>(
+    client: &Client,
+    name: Option<String>,
+    poll: Duration,
+    input: &mut R,
+    output: &mut W,
+) -> Result<()> {
+    let joined = match client.lobby()?.first() {
+        Some(entry) => match client.join_match(&entry.id, name.clone()) {
+            Ok(ticket) => Some(ticket),
+            Err(ClientError::IllegalMove(_)) => None,
+            Err(err) => return Err(err.into()),
+        },
+        None => None,
+    };
+    let ticket = match joined {
+        Some(ticket) => ticket,
+        None => {
+            let ticket = client.open_match(name)?;
+            writeln!(
+                output,
+                "Opened game {}. Waiting for another player to join...",
+                ticket.id
+            )?;
+            ticket
+        }
+    };
+
+    let mut game = ticket.game.clone();
+    let mut shown = None;
+    loop {
+        if game.state != GameState::Waiting && shown != Some(game.moves.len()) {
+            if shown.is_none() {
+                let (x, o) = (&game.x, game.o.as_deref().unwrap_or_default());
+                writeln!(output, "{x} (X) plays {o} (O). You play {}.", ticket.symbol)?;
+            }
+            render(game.decode_board(), output)?;
+            shown = Some(game.moves.len());
+        }
+        if game.state.is_over() {
+            break;
+        }
+
+        if game.to_move == Some(ticket.symbol) {
+            let Some(coord) = read_move(input, output)? else {
+                return Ok(());
+            };
+            match client.play_match(&ticket, coord) {
+                Ok(view) => game = view,
+                Err(ClientError::IllegalMove(message)) => {
+                    writeln!(output, "That move is not allowed: {message}")?;
+                }
+                Err(err) => return Err(err.into()),
+            }
+        } else {
+            thread::sleep(poll);
+            game = client.match_view(&ticket.id)?;
+        }
+    }
+
+    let message = match game.winner {
+        Some(winner) if winner == ticket.symbol && game.state == GameState::Resigned => {
+            "Your opponent left the game. You win!".to_string()
+        }
+        Some(winner) if winner == ticket.symbol => "You win!".to_string(),
+        Some(_) if game.state == GameState::Resigned => {
+            "You took too long to move and forfeited the game.".to_string()
+        }
+        Some(Symbol::X) => format!("{} wins.", game.x),
+        Some(Symbol::O) => format!("{} wins.", game.o.unwrap_or_default()),
+        None => "It's a draw.".to_string(),
+    };
+    writeln!(output, "{message}")?;
+    Ok(())
+}
+
+/// Asks for a move until the player types a valid one.
+///
+/// Returns `None` if the player quits or the input ends.
+fn read_move<R: BufRead, W: Write>(input: &mut R, output: &mut W) -> Result<Option<Coord>> {
+    loop {
+        write!(output, "Your move (row col, keypad 1-9, or q): ")?;
+        output.flush()?;
+
+        let mut line = String::new();
+        if input.read_line(&mut line)? == 0 {
+            writeln!(output)?;
+            return Ok(None);
+        }
+        match parse_command(&line) {
+            Some(Command::Move(coord)) => return Ok(Some(coord)),
+            Some(Command::Quit) => return Ok(None),
+            None => writeln!(
+                output,
+                "Please enter a row and column from 1 to 3, such as 2 2."
+            )?,
+        }
+    }
+}
+
+/// Writes the board of a game.
+fn render<W: Write>(board: Result<Board, GameError>, output: &mut W) -> Result<()> {
+    match board {
+        Ok(board) => writeln!(output, "\n{board}\n")?,
+        Err(err) => bail!("The server sent an invalid board: {err}"),
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_command() {
+        let coord = |row, col| Some(Command::Move(Coord::new(row, col).unwrap()));
+        assert_eq!(parse_command("1 3\n"), coord(1, 3));
+        assert_eq!(parse_command(" 2,2 "), coord(2, 2));
+        assert_eq!(parse_command("7"), coord(1, 1));
+        assert_eq!(parse_command("Q"), Some(Command::Quit));
+        assert_eq!(parse_command("4 1"), None);
+        assert_eq!(parse_command("0"), None);
+        assert_eq!(parse_command("middle"), None);
+        assert_eq!(parse_command(""), None);
+    }
+}
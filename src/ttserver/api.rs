@@ -0,0 +1,303 @@
+// SPDX-FileCopyrightText: 2022 - 2024 Ali Sajid Imami
+//
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use std::sync::Mutex;
+
+use serde::{
+    de::DeserializeOwned,
+    Serialize,
+};
+use tictacrustle::{
+    Coord,
+    ErrorResponse,
+    JoinRequest,
+    MatchMove,
+    NewGameRequest,
+};
+
+use crate::state::{
+    ApiError,
+    ServerState,
+};
+
+/// A response ready to be sent, independent of the HTTP library.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Response {
+    /// The HTTP status code.
+    pub status: u16,
+    /// The JSON body.
+    pub body:   String,
+}
+
+impl Response {
+    /// Creates a response with a JSON body.
+    fn json<T: Serialize>(status: u16, value: &T) -> Self {
+        match serde_json::to_string(value) {
+            Ok(body) => Self { status, body },
+            Err(err) => Self::error(&ApiError::Internal(err.to_string())),
+        }
+    }
+
+    /// Creates the response for an error.
+    pub fn error(error: &ApiError) -> Self {
+        Self::json(
+            error.status(),
+            &ErrorResponse {
+                error: error.to_string(),
+            },
+        )
+    }
+}
+
+/// Routes a request to the handler for its method and path.
+///
+/// | Method | Path                    | Action                                           |
+/// |--------|-------------------------|--------------------------------------------------|
+/// | POST   | `/games`                | Start a game against MENACE.                     |
+/// | GET    | `/games/{id}`           | Describe a game.                                 |
+/// | POST   | `/games/{id}/moves`     | Play a move; MENACE replies in the same response. |
+/// | GET    | `/games/{id}/outcome`   | Report how a finished game ended.                |
+/// | GET    | `/games/{id}/log`       | List the moves played, with their timing.        |
+/// | GET    | `/admin/sessions`       | List the games being kept; needs the admin token. |
+/// | POST   | `/lobby`                | Open a game for another human to join.           |
+/// | GET    | `/lobby`                | List the games waiting for a second player.      |
+/// | POST   | `/lobby/{id}/join`      | Join a game as `O`.                              |
+/// | GET    | `/lobby/{id}`           | Describe a game between humans.                  |
+/// | POST   | `/lobby/{id}/moves`     | Play a move with the token from opening or joining. |
+///
+/// The WebSockets at `/games/{id}/events` and `/lobby/{id}/events` are served on a port of their
+/// own by the `websocket` module.
+///
+/// # Arguments
+///
+/// * `state` - The shared server state.
+/// * `method` - The HTTP method, such as `GET`.
+/// * `path` - The request path, possibly with a query string.
+/// * `body` - The request body.
+/// * `authorization` - The `Authorization` header of the request, if any.
+pub fn route(
+    state: &Mutex<ServerState>,
+    method: &str,
+    path: &str,
+    body: &str,
+    authorization: Option<&str>,
+) -> Response {
+    let path = path.split('?').next().unwrap_or_default();
+    let segments: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+    let Ok(mut state) = state.lock() else {
+        return Response::error(&ApiError::Unavailable(
+            "The server state is unavailable".into(),
+        ));
+    };
+
+    let result = match (method, segments.as_slice()) {
+        ("POST", ["games"]) => parse_optional::<NewGameRequest>(body)
+            .map(|request| Response::json(201, &state.create_game(request))),
+        ("GET", ["games", id]) => state.view(id).map(|view| Response::json(200, &view)),
+        ("POST", ["games", id, "moves"]) => parse::<Coord>(body)
+            .and_then(|coord| state.play(id, coord))
+            .map(|response| Response::json(200, &response)),
+        ("GET", ["games", id, "outcome"]) => state
+            .outcome(id)
+            .map(|outcome| Response::json(200, &outcome)),
+        ("GET", ["games", id, "log"]) => state.log(id).map(|log| Response::json(200, &log)),
+        ("GET", ["admin", "sessions"]) => state
+            .authorize_admin(authorization)
+            .map(|()| Response::json(200, &state.sessions())),
+        ("POST", ["lobby"]) => parse_optional::<JoinRequest>(body)
+            .map(|request| Response::json(201, &state.open_match(request))),
+        ("GET", ["lobby"]) => Ok(Response::json(200, &state.lobby())),
+        ("POST", ["lobby", id, "join"]) => parse_optional::<JoinRequest>(body)
+            .and_then(|request| state.join_match(id, request))
+            .map(|ticket| Response::json(200, &ticket)),
+        ("GET", ["lobby", id]) => state.match_view(id).map(|view| Response::json(200, &view)),
+        ("POST", ["lobby", id, "moves"]) => parse::<MatchMove>(body)
+            .and_then(|request| state.play_match(id, &request))
+            .map(|view| Response::json(200, &view)),
+        _ => Err(ApiError::NotFound(format!("No route for {method} {path}"))),
+    };
+    result.unwrap_or_else(|error| Response::error(&error))
+}
+
+/// Parses a JSON request body.
+fn parse<T: DeserializeOwned>(body: &str) -> Result<T, ApiError> {
+    serde_json::from_str(body).map_err(|err| ApiError::BadRequest(err.to_string()))
+}
+
+/// Parses a JSON request body, using the default value if the body is empty.
+fn parse_optional<T: DeserializeOwned + Default>(body: &str) -> Result<T, ApiError> {
+    if body.trim().is_empty() {
+        Ok(T::default())
+    } else {
+        parse(body)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tictacrustle::{
+        GameView,
+        LobbyEntry,
+        MatchView,
+        MoveRecord,
+        MoveResponse,
+        PlayerTicket,
+        SessionSummary,
+    };
+
+    use super::*;
+
+    fn state() -> Mutex<ServerState> {
+        Mutex::new(crate::state::tests::state())
+    }
+
+    #[test]
+    fn test_game_round_trip() {
+        let state = state();
+        let created = route(&state, "POST", "/games", "", None);
+        assert_eq!(created.status, 201);
+        let game: GameView = serde_json::from_str(&created.body).unwrap();
+
+        let moved = route(
+            &state,
+            "POST",
+            &format!("/games/{}/moves", game.id),
+            r#"{"row": 2, "col": 2}"#,
+            None,
+        );
+        assert_eq!(moved.status, 200);
+        let response: MoveResponse = serde_json::from_str(&moved.body).unwrap();
+        assert_eq!(response.game.moves.len(), 2);
+
+        let fetched = route(&state, "GET", &format!("/games/{}", game.id), "", None);
+        assert_eq!(fetched.status, 200);
+        assert_eq!(
+            serde_json::from_str::<GameView>(&fetched.body).unwrap(),
+            response.game
+        );
+    }
+
+    #[test]
+    fn test_errors() {
+        let state = state();
+        let created = route(&state, "POST", "/games", r#"{"menace_first": true}"#, None);
+        let game: GameView = serde_json::from_str(&created.body).unwrap();
+        let path = format!("/games/{}/moves", game.id);
+        let taken = serde_json::to_string(&game.moves[0]).unwrap();
+
+        assert_eq!(
+            route(&state, "POST", &path, r#"{"row": 4, "col": 1}"#, None).status,
+            400
+        );
+        assert_eq!(route(&state, "POST", &path, "not json", None).status, 400);
+        assert_eq!(route(&state, "POST", &path, &taken, None).status, 409);
+        assert_eq!(route(&state, "GET", "/games/404", "", None).status, 404);
+        assert_eq!(route(&state, "DELETE", "/games", "", None).status, 404);
+
+        let outcome = route(
+            &state,
+            "GET",
+            &format!("/games/{}/outcome", game.id),
+            "",
+            None,
+        );
+        assert_eq!(outcome.status, 409);
+        assert!(outcome.body.contains("still in progress"));
+    }
+
+    #[test]
+    fn test_poisoned_state() {
+        let state = state();
+        let _ = std::panic::catch_unwind(|| {
+            let _guard = state.lock().unwrap();
+            panic!("poison the server state");
+        });
+
+        let response = route(&state, "GET", "/lobby", "", None);
+        assert_eq!(response.status, 503);
+        assert!(response.body.contains("unavailable"));
+    }
+
+    #[test]
+    fn test_log_and_admin_sessions() {
+        let state = state();
+        let created = route(&state, "POST", "/games", r#"{"menace_first": true}"#, None);
+        let game: GameView = serde_json::from_str(&created.body).unwrap();
+
+        let log = route(&state, "GET", &format!("/games/{}/log", game.id), "", None);
+        assert_eq!(log.status, 200);
+        let log: Vec<MoveRecord> = serde_json::from_str(&log.body).unwrap();
+        assert_eq!(log.len(), 1);
+        assert_eq!(log[0].coord, game.moves[0]);
+
+        assert_eq!(
+            route(&state, "GET", "/admin/sessions", "", None).status,
+            401
+        );
+        let sessions = route(&state, "GET", "/admin/sessions", "", Some("Bearer secret"));
+        assert_eq!(sessions.status, 200);
+        let sessions: Vec<SessionSummary> = serde_json::from_str(&sessions.body).unwrap();
+        assert_eq!(sessions.len(), 1);
+        assert_eq!(sessions[0].id, game.id);
+    }
+
+    #[test]
+    fn test_lobby_round_trip() {
+        let state = state();
+        let opened = route(&state, "POST", "/lobby", r#"{"name": "Ann"}"#, None);
+        assert_eq!(opened.status, 201);
+        let host: PlayerTicket = serde_json::from_str(&opened.body).unwrap();
+
+        let lobby: Vec<LobbyEntry> =
+            serde_json::from_str(&route(&state, "GET", "/lobby", "", None).body).unwrap();
+        assert_eq!(lobby.len(), 1);
+        assert_eq!(lobby[0].id, host.id);
+
+        let joined = route(
+            &state,
+            "POST",
+            &format!("/lobby/{}/join", host.id),
+            "",
+            None,
+        );
+        assert_eq!(joined.status, 200);
+        let guest: PlayerTicket = serde_json::from_str(&joined.body).unwrap();
+        assert_eq!(guest.game.o.as_deref(), Some("Player O"));
+        assert_eq!(
+            route(
+                &state,
+                "POST",
+                &format!("/lobby/{}/join", host.id),
+                "",
+                None
+            )
+            .status,
+            409
+        );
+
+        let path = format!("/lobby/{}/moves", host.id);
+        let body =
+            |token: &str| format!(r#"{{"token": "{token}", "coord": {{"row": 2, "col": 2}}}}"#);
+        assert_eq!(
+            route(&state, "POST", &path, &body(&guest.token), None).status,
+            409
+        );
+        assert_eq!(
+            route(&state, "POST", &path, &body("stranger"), None).status,
+            403
+        );
+        let moved = route(&state, "POST", &path, &body(&host.token), None);
+        assert_eq!(moved.status, 200);
+        let view: MatchView = serde_json::from_str(&moved.body).unwrap();
+        assert_eq!(view.to_move, Some(tictacrustle::Symbol::O));
+
+        let fetched = route(&state, "GET", &format!("/lobby/{}", host.id), "", None);
+        assert_eq!(
+            serde_json::from_str::<MatchView>(&fetched.body).unwrap(),
+            view
+        );
+        assert_eq!(route(&state, "GET", "/lobby/missing", "", None).status, 404);
+    }
+}
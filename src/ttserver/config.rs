@@ -0,0 +1,224 @@
+// SPDX-FileCopyrightText: 2022 - 2024 Ali Sajid Imami
+//
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use std::{
+    path::PathBuf,
+    time::Duration,
+};
+
+use anyhow::{
+    bail,
+    Context,
+    Result,
+};
+
+use crate::session::SessionConfig;
+
+/// The port the server listens on when none is configured.
+pub const DEFAULT_PORT: u16 = 8080;
+
+/// The port game events are streamed on when none is configured.
+pub const DEFAULT_EVENTS_PORT: u16 = 8081;
+
+/// The settings of the server, taken from the command line and the environment.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Config {
+    /// The address to listen on.
+    pub host:        String,
+    /// The port to listen on.
+    pub port:        u16,
+    /// The port to stream game events on over WebSockets.
+    pub events_port: u16,
+    /// The SQLite database MENACE is kept in, or `None` to keep it in memory.
+    pub database:    Option<PathBuf>,
+    /// How game sessions are expired.
+    pub sessions:    SessionConfig,
+    /// The bearer token that unlocks the `/admin` endpoints, or `None` to disable them.
+    pub admin_token: Option<String>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            host:        "127.0.0.1".to_string(),
+            port:        DEFAULT_PORT,
+            events_port: DEFAULT_EVENTS_PORT,
+            database:    None,
+            sessions:    SessionConfig::default(),
+            admin_token: None,
+        }
+    }
+}
+
+impl Config {
+    /// Builds the configuration from command-line arguments and environment variables.
+    ///
+    /// Command-line arguments take precedence over the `TTSERVER_HOST`, `TTSERVER_PORT`,
+    /// `TTSERVER_EVENTS_PORT`, `TTSERVER_DATABASE`, `TTSERVER_IDLE_TIMEOUT` and
+    /// `TTSERVER_ABANDON` environment variables, which take precedence over the defaults. The admin
+    /// token is only read from `TTSERVER_ADMIN_TOKEN`, so that it does not show up in the
+    /// process list.
+    ///
+    /// # Arguments
+    ///
+    /// * `args` - The command-line arguments, without the program name.
+    /// * `env` - Looks up an environment variable.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error for an unknown argument, a missing value, a port or idle timeout that is
+    /// not a number, or an unknown abandon policy.
+    pub fn from_args<I, E>(args: I, env: E) -> Result<Self>
+    where
+        I: IntoIterator<Item = String>,
+        E: Fn(&str) -> Option<String>,
+    {
+        let mut config = Self::default();
+        if let Some(host) = env("TTSERVER_HOST") {
+            config.host = host;
+        }
+        if let Some(port) = env("TTSERVER_PORT") {
+            config.port = parse_port(&port)?;
+        }
+        if let Some(port) = env("TTSERVER_EVENTS_PORT") {
+            config.events_port = parse_port(&port)?;
+        }
+        if let Some(database) = env("TTSERVER_DATABASE") {
+            config.database = Some(PathBuf::from(database));
+        }
+        if let Some(timeout) = env("TTSERVER_IDLE_TIMEOUT") {
+            config.sessions.idle_timeout = parse_timeout(&timeout)?;
+        }
+        if let Some(policy) = env("TTSERVER_ABANDON") {
+            config.sessions.abandon = policy.parse()?;
+        }
+        config.admin_token = env("TTSERVER_ADMIN_TOKEN").filter(|token| !token.is_empty());
+
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            let mut value =
+                |name: &str| args.next().with_context(|| format!("{name} needs a value"));
+            match arg.as_str() {
+                "--host" => config.host = value("--host")?,
+                "-p" | "--port" => config.port = parse_port(&value("--port")?)?,
+                "--events-port" => config.events_port = parse_port(&value("--events-port")?)?,
+                "--database" => config.database = Some(PathBuf::from(value("--database")?)),
+                "--idle-timeout" => {
+                    config.sessions.idle_timeout = parse_timeout(&value("--idle-timeout")?)?;
+                }
+                "--abandon" => config.sessions.abandon = value("--abandon")?.parse()?,
+                _ => bail!("Unknown argument {arg}\n\n{USAGE}"),
+            }
+        }
+        Ok(config)
+    }
+
+    /// Returns the address to listen on, such as `127.0.0.1:8080`.
+    pub fn address(&self) -> String {
+        format!("{}:{}", self.host, self.port)
+    }
+
+    /// Returns the address to stream game events on, such as `127.0.0.1:8081`.
+    pub fn events_address(&self) -> String {
+        format!("{}:{}", self.host, self.events_port)
+    }
+}
+
+/// Describes the command-line arguments.
+pub const USAGE: &str = "Usage: ttserver [--host HOST] [--port PORT] [--events-port PORT] \
+                         [--database PATH] [--idle-timeout SECONDS] [--abandon \
+                         ignore|win|draw|loss]";
+
+/// Parses a port number.
+fn parse_port(port: &str) -> Result<u16> {
+    port.parse()
+        .with_context(|| format!("Invalid port {port:?}"))
+}
+
+/// Parses an idle timeout given in seconds.
+fn parse_timeout(seconds: &str) -> Result<Duration> {
+    let seconds = seconds
+        .parse()
+        .with_context(|| format!("Invalid idle timeout {seconds:?}"))?;
+    if seconds == 0 {
+        bail!("The idle timeout must be at least one second");
+    }
+    Ok(Duration::from_secs(seconds))
+}
+
+#[cfg(test)]
+mod tests {
+    use tictacrustle::GameResult;
+
+    use super::*;
+    use crate::session::AbandonPolicy;
+
+    fn args(values: &[&str]) -> Vec<String> {
+        values.iter().map(ToString::to_string).collect()
+    }
+
+    #[test]
+    fn test_defaults() {
+        let config = Config::from_args(args(&[]), |_| None).unwrap();
+        assert_eq!(config, Config::default());
+        assert_eq!(config.address(), "127.0.0.1:8080");
+        assert_eq!(config.events_address(), "127.0.0.1:8081");
+    }
+
+    #[test]
+    fn test_arguments_override_environment() {
+        let env = |name: &str| match name {
+            "TTSERVER_PORT" => Some("9000".to_string()),
+            "TTSERVER_DATABASE" => Some("env.sqlite".to_string()),
+            _ => None,
+        };
+        let config = Config::from_args(args(&["--port", "9100"]), env).unwrap();
+        assert_eq!(config.port, 9100);
+        assert_eq!(config.database, Some(PathBuf::from("env.sqlite")));
+    }
+
+    #[test]
+    fn test_events_port() {
+        let env = |name: &str| (name == "TTSERVER_EVENTS_PORT").then(|| "9001".to_string());
+        assert_eq!(Config::from_args(args(&[]), env).unwrap().events_port, 9001);
+
+        let config = Config::from_args(args(&["--events-port", "9101"]), env).unwrap();
+        assert_eq!(config.events_address(), "127.0.0.1:9101");
+        assert!(Config::from_args(args(&["--events-port", "ws"]), |_| None).is_err());
+    }
+
+    #[test]
+    fn test_rejects_bad_arguments() {
+        assert!(Config::from_args(args(&["--port", "http"]), |_| None).is_err());
+        assert!(Config::from_args(args(&["--port"]), |_| None).is_err());
+        assert!(Config::from_args(args(&["--verbose"]), |_| None).is_err());
+    }
+
+    #[test]
+    fn test_session_settings() {
+        let env = |name: &str| (name == "TTSERVER_ABANDON").then(|| "ignore".to_string());
+        let config = Config::from_args(args(&["--idle-timeout", "90"]), env).unwrap();
+        assert_eq!(config.sessions.idle_timeout, Duration::from_secs(90));
+        assert_eq!(config.sessions.abandon, AbandonPolicy::Ignore);
+
+        let config = Config::from_args(args(&["--abandon", "draw"]), |_| None).unwrap();
+        assert_eq!(
+            config.sessions.abandon,
+            AbandonPolicy::Reinforce(GameResult::Draw)
+        );
+        assert!(Config::from_args(args(&["--idle-timeout", "0"]), |_| None).is_err());
+        assert!(Config::from_args(args(&["--abandon", "later"]), |_| None).is_err());
+    }
+
+    #[test]
+    fn test_admin_token() {
+        let env = |name: &str| (name == "TTSERVER_ADMIN_TOKEN").then(|| "secret".to_string());
+        let config = Config::from_args(args(&[]), env).unwrap();
+        assert_eq!(config.admin_token.as_deref(), Some("secret"));
+
+        let env = |name: &str| (name == "TTSERVER_ADMIN_TOKEN").then(String::new);
+        assert_eq!(Config::from_args(args(&[]), env).unwrap().admin_token, None);
+    }
+}
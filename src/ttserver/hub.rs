@@ -0,0 +1,211 @@
+// SPDX-FileCopyrightText: 2022 - 2024 Ali Sajid Imami
+//
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use std::{
+    collections::HashMap,
+    ops::Deref,
+    sync::{
+        mpsc::{
+            self,
+            Receiver,
+            Sender,
+        },
+        Arc,
+        Weak,
+    },
+};
+
+use tictacrustle::GameEvent;
+
+/// The most clients that may watch one game at a time.
+pub const MAX_SUBSCRIBERS_PER_GAME: usize = 32;
+
+/// The most clients that may watch games at a time, over all games together.
+pub const MAX_SUBSCRIBERS: usize = 256;
+
+/// Hands the events of each game to the clients watching it.
+///
+/// Every subscriber gets its own channel. A subscriber stops counting against the limits as soon
+/// as its [`Subscription`] is dropped, and is forgotten the next time an event is published to
+/// its game or another client subscribes.
+#[derive(Debug)]
+pub struct Hub {
+    /// The subscribers, keyed by game identifier.
+    subscribers:  HashMap<String, Vec<Subscriber>>,
+    /// The most subscribers one game may have.
+    max_per_game: usize,
+    /// The most subscribers all games together may have.
+    max_total:    usize,
+}
+
+/// The sending end of one subscription.
+#[derive(Debug)]
+struct Subscriber {
+    /// The channel the events are sent on.
+    sender: Sender<GameEvent>,
+    /// Alive for as long as the [`Subscription`] is.
+    alive:  Weak<()>,
+}
+
+impl Subscriber {
+    /// Checks whether the subscription has not been dropped.
+    fn is_alive(&self) -> bool {
+        self.alive.strong_count() > 0
+    }
+}
+
+/// The receiving end of a subscription to the events of a game.
+#[derive(Debug)]
+pub struct Subscription {
+    /// The channel the events arrive on.
+    receiver: Receiver<GameEvent>,
+    /// Keeps the subscription counted by the hub until it is dropped.
+    _alive:   Arc<()>,
+}
+
+impl Deref for Subscription {
+    type Target = Receiver<GameEvent>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.receiver
+    }
+}
+
+impl Hub {
+    /// Creates a hub without subscribers, limited to [`MAX_SUBSCRIBERS_PER_GAME`] subscribers
+    /// per game and [`MAX_SUBSCRIBERS`] in total.
+    pub fn new() -> Self {
+        Self::with_limits(MAX_SUBSCRIBERS_PER_GAME, MAX_SUBSCRIBERS)
+    }
+
+    /// Creates a hub without subscribers and with the given limits.
+    ///
+    /// # Arguments
+    ///
+    /// * `max_per_game` - The most subscribers one game may have.
+    /// * `max_total` - The most subscribers all games together may have.
+    pub fn with_limits(max_per_game: usize, max_total: usize) -> Self {
+        Self {
+            subscribers: HashMap::new(),
+            max_per_game,
+            max_total,
+        }
+    }
+
+    /// Subscribes to the events of a game.
+    ///
+    /// Returns `None` if the game or the hub already has as many subscribers as allowed.
+    pub fn subscribe(&mut self, id: &str) -> Option<Subscription> {
+        self.subscribers.retain(|_, subscribers| {
+            subscribers.retain(Subscriber::is_alive);
+            !subscribers.is_empty()
+        });
+        let total: usize = self.subscribers.values().map(Vec::len).sum();
+        if self.subscribers(id) >= self.max_per_game || total >= self.max_total {
+            return None;
+        }
+
+        let (sender, receiver) = mpsc::channel();
+        let alive = Arc::new(());
+        self.subscribers
+            .entry(id.to_string())
+            .or_default()
+            .push(Subscriber {
+                sender,
+                alive: Arc::downgrade(&alive),
+            });
+        Some(Subscription {
+            receiver,
+            _alive: alive,
+        })
+    }
+
+    /// Sends an event to every subscriber of a game.
+    pub fn publish(&mut self, id: &str, event: &GameEvent) {
+        if let Some(subscribers) = self.subscribers.get_mut(id) {
+            subscribers.retain(|subscriber| subscriber.sender.send(event.clone()).is_ok());
+            if subscribers.is_empty() {
+                self.subscribers.remove(id);
+            }
+        }
+    }
+
+    /// Sends a last event to the subscribers of a game and disconnects them.
+    pub fn close(&mut self, id: &str, event: &GameEvent) {
+        self.publish(id, event);
+        self.subscribers.remove(id);
+    }
+
+    /// Returns the number of subscribers to a game.
+    pub fn subscribers(&self, id: &str) -> usize {
+        self.subscribers.get(id).map_or(0, |subscribers| {
+            subscribers
+                .iter()
+                .filter(|subscriber| subscriber.is_alive())
+                .count()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::mpsc::TryRecvError;
+
+    use super::*;
+
+    fn expired(id: &str) -> GameEvent {
+        GameEvent::Expired { id: id.to_string() }
+    }
+
+    #[test]
+    fn test_events_reach_only_their_game() {
+        let mut hub = Hub::new();
+        let first = hub.subscribe("a").unwrap();
+        let second = hub.subscribe("a").unwrap();
+        let other = hub.subscribe("b").unwrap();
+
+        hub.publish("a", &expired("a"));
+        assert_eq!(first.try_recv().unwrap(), expired("a"));
+        assert_eq!(second.try_recv().unwrap(), expired("a"));
+        assert_eq!(other.try_recv(), Err(TryRecvError::Empty));
+    }
+
+    #[test]
+    fn test_dropped_subscribers_are_forgotten() {
+        let mut hub = Hub::new();
+        let kept = hub.subscribe("a").unwrap();
+        drop(hub.subscribe("a"));
+        assert_eq!(hub.subscribers("a"), 1);
+        hub.publish("a", &expired("a"));
+        assert_eq!(hub.subscribers("a"), 1);
+
+        drop(kept);
+        hub.publish("a", &expired("a"));
+        assert_eq!(hub.subscribers("a"), 0);
+    }
+
+    #[test]
+    fn test_close_disconnects() {
+        let mut hub = Hub::new();
+        let receiver = hub.subscribe("a").unwrap();
+        hub.close("a", &expired("a"));
+        assert_eq!(receiver.recv().unwrap(), expired("a"));
+        assert_eq!(receiver.recv(), Err(mpsc::RecvError));
+    }
+
+    #[test]
+    fn test_limits() {
+        let mut hub = Hub::with_limits(2, 3);
+        let first = hub.subscribe("a").unwrap();
+        let _second = hub.subscribe("a").unwrap();
+        assert!(hub.subscribe("a").is_none());
+
+        let _third = hub.subscribe("b").unwrap();
+        assert!(hub.subscribe("c").is_none());
+
+        drop(first);
+        assert!(hub.subscribe("c").is_some());
+    }
+}
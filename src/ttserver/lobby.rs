@@ -0,0 +1,380 @@
+// SPDX-FileCopyrightText: 2022 - 2024 Ali Sajid Imami
+//
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use std::time::{
+    Duration,
+    Instant,
+};
+
+use tictacrustle::{
+    BoardCodec,
+    Coord,
+    Game,
+    GameEvent,
+    GameState,
+    GameStatus,
+    LobbyEntry,
+    MatchView,
+    MoveRecord,
+    Symbol,
+};
+
+use crate::{
+    session::millis,
+    state::ApiError,
+};
+
+/// The longest player name kept; longer names are cut.
+pub const MAX_NAME_LENGTH: usize = 32;
+
+/// A player seated at a game between two humans.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Seat {
+    /// The name shown to the other player.
+    pub name:  String,
+    /// The secret that authorizes the player's moves.
+    pub token: String,
+}
+
+impl Seat {
+    /// Seats a player, naming them after their symbol if they gave no name.
+    pub fn new(name: Option<String>, symbol: Symbol, token: String) -> Self {
+        let name = name
+            .map(|name| {
+                name.trim()
+                    .chars()
+                    .take(MAX_NAME_LENGTH)
+                    .collect::<String>()
+            })
+            .filter(|name| !name.is_empty())
+            .unwrap_or_else(|| format!("Player {symbol}"));
+        Self { name, token }
+    }
+}
+
+/// A game between two humans, opened in the lobby by `X` and joined by `O`.
+#[derive(Debug, Clone)]
+pub struct Match {
+    /// The authoritative state of the game.
+    pub game:        Game,
+    /// The player who opened the game and plays `X`.
+    pub x:           Seat,
+    /// The player who joined the game and plays `O`, once someone has.
+    pub o:           Option<Seat>,
+    /// The moves played, in order.
+    pub log:         Vec<MoveRecord>,
+    /// The player who forfeited by going idle on their turn, if any.
+    pub forfeited:   Option<Symbol>,
+    /// When the game was opened.
+    pub created:     Instant,
+    /// When the game last changed: it was opened or joined, a move was played, or a player
+    /// forfeited. Viewing the game does not count, so the player to move has been idle since
+    /// this time.
+    pub last_active: Instant,
+}
+
+impl Match {
+    /// Opens a game with its first player.
+    pub fn new(x: Seat) -> Self {
+        let now = Instant::now();
+        Self {
+            game: Game::new(),
+            x,
+            o: None,
+            log: Vec::new(),
+            forfeited: None,
+            created: now,
+            last_active: now,
+        }
+    }
+
+    /// Seats the second player.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ApiError::Conflict`] if the game already has two players.
+    pub fn join(&mut self, o: Seat) -> Result<(), ApiError> {
+        if self.o.is_some() {
+            return Err(ApiError::Conflict(
+                "The game already has two players".to_string(),
+            ));
+        }
+        self.o = Some(o);
+        self.touch();
+        Ok(())
+    }
+
+    /// Returns the symbol of the player holding a token.
+    pub fn seat(&self, token: &str) -> Option<Symbol> {
+        if self.x.token == token {
+            Some(Symbol::X)
+        } else if self.o.as_ref().is_some_and(|o| o.token == token) {
+            Some(Symbol::O)
+        } else {
+            None
+        }
+    }
+
+    /// Plays a move for the player holding a token, following the rules of [`Game`].
+    ///
+    /// # Errors
+    ///
+    /// - [`ApiError::Forbidden`] if the token belongs to neither player.
+    /// - [`ApiError::Conflict`] if the game is waiting for a second player, is over, it is not the
+    ///   player's turn, or the square is taken.
+    pub fn play(&mut self, token: &str, coord: Coord) -> Result<MoveRecord, ApiError> {
+        let player = self
+            .seat(token)
+            .ok_or_else(|| ApiError::Forbidden("The token does not belong to this game".into()))?;
+        match self.state() {
+            GameState::Waiting => {
+                return Err(ApiError::Conflict(
+                    "The game is waiting for a second player".to_string(),
+                ))
+            }
+            GameState::InProgress => {}
+            _ => return Err(ApiError::Conflict("The game is over".to_string())),
+        }
+        if self.game.status().to_move() != Some(player) {
+            return Err(ApiError::Conflict("It is not your turn".to_string()));
+        }
+
+        self.game.play(coord)?;
+        let record = MoveRecord {
+            player,
+            coord,
+            elapsed_ms: millis(self.created.elapsed()),
+        };
+        self.log.push(record);
+        self.touch();
+        Ok(record)
+    }
+
+    /// Ends a game in progress by making the player to move forfeit it.
+    ///
+    /// # Arguments
+    ///
+    /// * `now` - The time the game ended.
+    ///
+    /// # Returns
+    ///
+    /// The player who forfeited, or `None` if the game was not in progress.
+    pub fn forfeit(&mut self, now: Instant) -> Option<Symbol> {
+        if self.state() != GameState::InProgress {
+            return None;
+        }
+        let player = self.game.status().to_move()?;
+        self.forfeited = Some(player);
+        self.last_active = now;
+        Some(player)
+    }
+
+    /// Marks the game as active now.
+    pub fn touch(&mut self) {
+        self.last_active = Instant::now();
+    }
+
+    /// Checks whether the game has gone without changing for at least `timeout` at `now`.
+    pub fn is_idle(&self, now: Instant, timeout: Duration) -> bool {
+        now.saturating_duration_since(self.last_active) >= timeout
+    }
+
+    /// Returns where the game stands.
+    pub fn state(&self) -> GameState {
+        match self.game.status() {
+            _ if self.o.is_none() => GameState::Waiting,
+            _ if self.forfeited.is_some() => GameState::Resigned,
+            GameStatus::Won { .. } => GameState::Won,
+            GameStatus::Draw => GameState::Draw,
+            GameStatus::InProgress { .. } => GameState::InProgress,
+        }
+    }
+
+    /// Describes the game for a client.
+    pub fn view(&self, id: &str) -> MatchView {
+        let state = self.state();
+        let status = self.game.status();
+        MatchView {
+            id: id.to_string(),
+            board: BoardCodec::new().encode(self.game.board()),
+            x: self.x.name.clone(),
+            o: self.o.as_ref().map(|o| o.name.clone()),
+            state,
+            to_move: (state == GameState::InProgress)
+                .then(|| status.to_move())
+                .flatten(),
+            winner: self.forfeited.map(Symbol::opponent).or(status.winner()),
+            moves: self.log.iter().map(|record| record.coord).collect(),
+        }
+    }
+
+    /// Builds the events that tell both players and any spectators where the game stands: the
+    /// game, followed by the player to move or how the game ended.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The identifier of the game.
+    /// * `last_move` - The move that changed the board, if the events announce one.
+    pub fn events(&self, id: &str, last_move: Option<MoveRecord>) -> Vec<GameEvent> {
+        let view = self.view(id);
+        let status = match view.state {
+            GameState::Waiting => None,
+            GameState::InProgress => view.to_move.map(|to_move| GameEvent::Turn {
+                id: id.to_string(),
+                to_move,
+            }),
+            state => Some(GameEvent::Finished {
+                id: id.to_string(),
+                state,
+                winner: view.winner,
+            }),
+        };
+        let board = GameEvent::Match {
+            game: view,
+            last_move,
+        };
+        std::iter::once(board).chain(status).collect()
+    }
+
+    /// Describes the game for the lobby.
+    pub fn entry(&self, id: &str, now: Instant) -> LobbyEntry {
+        LobbyEntry {
+            id:           id.to_string(),
+            host:         self.x.name.clone(),
+            waiting_secs: now.saturating_duration_since(self.created).as_secs(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn coord(row: usize, col: usize) -> Coord {
+        Coord::new(row, col).unwrap()
+    }
+
+    fn joined() -> Match {
+        let mut game = Match::new(Seat::new(Some("Ann".into()), Symbol::X, "x".into()));
+        game.join(Seat::new(None, Symbol::O, "o".into())).unwrap();
+        game
+    }
+
+    #[test]
+    fn test_seats() {
+        let game = joined();
+        assert_eq!(game.x.name, "Ann");
+        assert_eq!(game.o.as_ref().unwrap().name, "Player O");
+        assert_eq!(game.seat("x"), Some(Symbol::X));
+        assert_eq!(game.seat("o"), Some(Symbol::O));
+        assert_eq!(game.seat("z"), None);
+
+        let long = Seat::new(Some("n".repeat(40)), Symbol::X, String::new());
+        assert_eq!(long.name.len(), MAX_NAME_LENGTH);
+        assert_eq!(
+            Seat::new(Some("  ".into()), Symbol::X, String::new()).name,
+            "Player X"
+        );
+    }
+
+    #[test]
+    fn test_waiting_for_a_second_player() {
+        let mut game = Match::new(Seat::new(None, Symbol::X, "x".into()));
+        assert_eq!(game.state(), GameState::Waiting);
+        assert_eq!(game.view("a").to_move, None);
+        assert!(matches!(
+            game.play("x", coord(1, 1)),
+            Err(ApiError::Conflict(_))
+        ));
+
+        game.join(Seat::new(None, Symbol::O, "o".into())).unwrap();
+        assert!(matches!(
+            game.join(Seat::new(None, Symbol::O, "p".into())),
+            Err(ApiError::Conflict(_))
+        ));
+        assert_eq!(game.view("a").to_move, Some(Symbol::X));
+    }
+
+    #[test]
+    fn test_turn_order() {
+        let mut game = joined();
+        assert!(matches!(
+            game.play("o", coord(1, 1)),
+            Err(ApiError::Conflict(_))
+        ));
+        assert!(matches!(
+            game.play("z", coord(1, 1)),
+            Err(ApiError::Forbidden(_))
+        ));
+
+        let record = game.play("x", coord(1, 1)).unwrap();
+        assert_eq!(record.player, Symbol::X);
+        assert!(matches!(
+            game.play("x", coord(2, 2)),
+            Err(ApiError::Conflict(_))
+        ));
+        assert!(matches!(
+            game.play("o", coord(1, 1)),
+            Err(ApiError::Conflict(_))
+        ));
+        assert_eq!(game.play("o", coord(2, 2)).unwrap().player, Symbol::O);
+    }
+
+    #[test]
+    fn test_win_is_announced() {
+        let mut game = joined();
+        for (token, row, col) in [
+            ("x", 1, 1),
+            ("o", 2, 1),
+            ("x", 1, 2),
+            ("o", 2, 2),
+            ("x", 1, 3),
+        ] {
+            game.play(token, coord(row, col)).unwrap();
+        }
+        assert!(matches!(
+            game.play("o", coord(3, 3)),
+            Err(ApiError::Conflict(_))
+        ));
+
+        let events = game.events("a", game.log.last().copied());
+        assert_eq!(
+            events[1],
+            GameEvent::Finished {
+                id:     "a".to_string(),
+                state:  GameState::Won,
+                winner: Some(Symbol::X),
+            }
+        );
+    }
+
+    #[test]
+    fn test_forfeit() {
+        let mut game = Match::new(Seat::new(None, Symbol::X, "x".into()));
+        assert_eq!(game.forfeit(Instant::now()), None);
+
+        game.join(Seat::new(None, Symbol::O, "o".into())).unwrap();
+        game.play("x", coord(1, 1)).unwrap();
+        assert_eq!(game.forfeit(Instant::now()), Some(Symbol::O));
+        assert_eq!(game.state(), GameState::Resigned);
+        assert_eq!(game.view("a").winner, Some(Symbol::X));
+        assert_eq!(game.view("a").to_move, None);
+        assert!(matches!(
+            game.play("o", coord(2, 2)),
+            Err(ApiError::Conflict(_))
+        ));
+        assert_eq!(game.forfeit(Instant::now()), None);
+
+        let events = game.events("a", None);
+        assert_eq!(
+            events[1],
+            GameEvent::Finished {
+                id:     "a".to_string(),
+                state:  GameState::Resigned,
+                winner: Some(Symbol::X),
+            }
+        );
+    }
+}
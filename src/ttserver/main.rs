@@ -0,0 +1,240 @@
+// SPDX-FileCopyrightText: 2022 - 2024 Ali Sajid Imami
+//
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! `ttserver` hosts MENACE behind an HTTP JSON API, so `ttrustle` clients can play against it.
+
+mod api;
+mod config;
+mod hub;
+mod lobby;
+mod session;
+mod state;
+mod websocket;
+
+use std::{
+    io::Read,
+    net::TcpListener,
+    sync::{
+        Arc,
+        Mutex,
+    },
+    thread,
+    time::{
+        Duration,
+        Instant,
+    },
+};
+
+use anyhow::{
+    anyhow,
+    Context,
+    Result,
+};
+use log::{
+    info,
+    warn,
+};
+use rand::SeedableRng;
+use tictacrustle::{
+    MatchboxStore,
+    MemoryStore,
+    Menace,
+    SeededRng,
+    SqliteStore,
+};
+use tiny_http::{
+    Header,
+    Request,
+    Server,
+};
+
+use crate::{
+    config::Config,
+    state::ServerState,
+};
+
+/// The number of threads answering requests.
+const WORKERS: usize = 4;
+
+/// The longest time between two sweeps for idle games.
+const MAX_SWEEP_INTERVAL: Duration = Duration::from_secs(30);
+
+/// The largest request body accepted, in bytes. Every body the API reads is far smaller.
+const MAX_BODY: usize = 16 * 1024;
+
+fn main() -> Result<()> {
+    env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("info")).init();
+    let config = Config::from_args(std::env::args().skip(1), |name| std::env::var(name).ok())?;
+
+    let store: Box<dyn MatchboxStore + Send> = match &config.database {
+        Some(path) => Box::new(SqliteStore::open(path)?),
+        None => Box::new(MemoryStore::new()),
+    };
+    let menace = Menace::from_store(store.as_ref())?;
+    info!(
+        "Loaded MENACE with {} matchboxes after {} games",
+        menace.len(),
+        menace.games_played()
+    );
+
+    let state = Arc::new(Mutex::new(ServerState::new(
+        menace,
+        store,
+        SeededRng::from_os_rng(),
+        config.sessions,
+        config.admin_token.clone(),
+    )));
+    if config.admin_token.is_none() {
+        info!("No admin token is set; the admin endpoints are disabled");
+    }
+    info!(
+        "Games expire after {} seconds idle; abandoned games count as {}",
+        config.sessions.idle_timeout.as_secs(),
+        config.sessions.abandon
+    );
+    spawn_sweeper(Arc::clone(&state), config.sessions.idle_timeout);
+    let server = Arc::new(Server::http(config.address()).map_err(|err| anyhow!(err))?);
+    info!("Listening on http://{}", config.address());
+    let events = TcpListener::bind(config.events_address())
+        .with_context(|| format!("Could not listen on {}", config.events_address()))?;
+    info!("Streaming game events on ws://{}", config.events_address());
+    let streaming = Arc::clone(&state);
+    thread::spawn(move || websocket::listen(&streaming, &events));
+
+    let workers: Vec<_> = (0..WORKERS)
+        .map(|_| {
+            let server = Arc::clone(&server);
+            let state = Arc::clone(&state);
+            thread::spawn(move || {
+                while let Ok(request) = server.recv() {
+                    handle(&state, request);
+                }
+            })
+        })
+        .collect();
+    for worker in workers {
+        worker
+            .join()
+            .map_err(|_| anyhow!("A worker thread panicked"))?;
+    }
+    Ok(())
+}
+
+/// Starts a thread that periodically expires idle games.
+fn spawn_sweeper(state: Arc<Mutex<ServerState>>, idle_timeout: Duration) {
+    let interval = idle_timeout.min(MAX_SWEEP_INTERVAL);
+    thread::spawn(move || loop {
+        thread::sleep(interval);
+        let Ok(mut state) = state.lock() else {
+            warn!("The server state is unavailable; no longer expiring idle games");
+            return;
+        };
+        let expired = state.expire_idle(Instant::now());
+        if expired > 0 {
+            info!("Expired {expired} idle games");
+        }
+    });
+}
+
+/// Answers a single HTTP request.
+fn handle(state: &Mutex<ServerState>, mut request: Request) {
+    let authorization = request
+        .headers()
+        .iter()
+        .find(|header| header.field.equiv("Authorization"))
+        .map(|header| header.value.to_string());
+    let length = request.body_length();
+    let response = match read_body(request.as_reader(), length) {
+        Ok(body) => api::route(
+            state,
+            request.method().as_str(),
+            request.url(),
+            &body,
+            authorization.as_deref(),
+        ),
+        Err(err) => api::Response::error(&err),
+    };
+    respond(request, &response);
+}
+
+/// Reads a request body of at most [`MAX_BODY`] bytes.
+///
+/// # Arguments
+///
+/// * `reader` - The body of the request.
+/// * `length` - The length the request declares in its `Content-Length` header, if any.
+///
+/// # Errors
+///
+/// - [`ApiError::PayloadTooLarge`](state::ApiError::PayloadTooLarge) if the declared or actual
+///   length is over the limit.
+/// - [`ApiError::BadRequest`](state::ApiError::BadRequest) if the body cannot be read as UTF-8.
+fn read_body(reader: impl Read, length: Option<usize>) -> Result<String, state::ApiError> {
+    let too_large = || {
+        state::ApiError::PayloadTooLarge(format!("Request bodies are limited to {MAX_BODY} bytes"))
+    };
+    if length.is_some_and(|length| length > MAX_BODY) {
+        return Err(too_large());
+    }
+
+    let mut body = String::new();
+    let limit = u64::try_from(MAX_BODY).unwrap_or(u64::MAX) + 1;
+    reader
+        .take(limit)
+        .read_to_string(&mut body)
+        .map_err(|err| state::ApiError::BadRequest(err.to_string()))?;
+    if body.len() > MAX_BODY {
+        return Err(too_large());
+    }
+    Ok(body)
+}
+
+/// Sends a JSON response and logs it.
+fn respond(request: Request, response: &api::Response) {
+    info!(
+        "{} {} -> {}",
+        request.method(),
+        request.url(),
+        response.status
+    );
+
+    let content_type = Header::from_bytes("Content-Type", "application/json")
+        .expect("the content type header is valid");
+    let reply = tiny_http::Response::from_string(response.body.as_str())
+        .with_status_code(response.status)
+        .with_header(content_type);
+    if let Err(err) = request.respond(reply) {
+        warn!("Could not send the response: {err}");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::ApiError;
+
+    #[test]
+    fn test_read_body() {
+        let body = r#"{"coord": {"row": 1, "col": 1}}"#;
+        assert_eq!(
+            read_body(body.as_bytes(), Some(body.len())),
+            Ok(body.to_string())
+        );
+
+        let oversize = "x".repeat(MAX_BODY + 1);
+        assert!(matches!(
+            read_body(oversize.as_bytes(), None),
+            Err(ApiError::PayloadTooLarge(_))
+        ));
+        assert!(matches!(
+            read_body("{}".as_bytes(), Some(MAX_BODY + 1)),
+            Err(ApiError::PayloadTooLarge(_))
+        ));
+        assert!(matches!(
+            read_body(&[0xff, 0xfe][..], None),
+            Err(ApiError::BadRequest(_))
+        ));
+    }
+}
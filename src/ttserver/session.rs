@@ -0,0 +1,304 @@
+// SPDX-FileCopyrightText: 2022 - 2024 Ali Sajid Imami
+//
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use std::{
+    fmt,
+    str::FromStr,
+    time::{
+        Duration,
+        Instant,
+    },
+};
+
+use anyhow::{
+    bail,
+    Error,
+};
+use tictacrustle::{
+    BeadDraw,
+    BoardCodec,
+    Coord,
+    Game,
+    GameEvent,
+    GameResult,
+    GameState,
+    GameStatus,
+    GameView,
+    MatchOutcome,
+    MoveRecord,
+    OutcomeView,
+    SessionSummary,
+    Symbol,
+};
+
+/// The idle time after which a game is expired when none is configured.
+pub const DEFAULT_IDLE_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// What happens to MENACE when a human abandons a game before it is over.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AbandonPolicy {
+    /// MENACE forgets the game and is not reinforced.
+    Ignore,
+    /// MENACE is reinforced as if the game had ended with the given result for the human.
+    Reinforce(GameResult),
+}
+
+impl Default for AbandonPolicy {
+    /// Abandoning a game forfeits it, so MENACE is reinforced for a win.
+    fn default() -> Self {
+        Self::Reinforce(GameResult::Loss)
+    }
+}
+
+impl fmt::Display for AbandonPolicy {
+    /// Formats the policy as `ignore`, `win`, `draw` or `loss`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Ignore => write!(f, "ignore"),
+            Self::Reinforce(GameResult::Win) => write!(f, "win"),
+            Self::Reinforce(GameResult::Draw) => write!(f, "draw"),
+            Self::Reinforce(GameResult::Loss) => write!(f, "loss"),
+        }
+    }
+}
+
+impl FromStr for AbandonPolicy {
+    type Err = Error;
+
+    /// Parses `ignore`, or the result an abandoned game counts as for the human: `win`, `draw`
+    /// or `loss`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "ignore" => Ok(Self::Ignore),
+            "win" => Ok(Self::Reinforce(GameResult::Win)),
+            "draw" => Ok(Self::Reinforce(GameResult::Draw)),
+            "loss" => Ok(Self::Reinforce(GameResult::Loss)),
+            _ => bail!("Invalid abandon policy {s:?}; expected ignore, win, draw or loss"),
+        }
+    }
+}
+
+/// How the server looks after its game sessions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SessionConfig {
+    /// How long a game may go without a request before it is expired.
+    pub idle_timeout: Duration,
+    /// What happens to MENACE when a game is expired before it is over.
+    pub abandon:      AbandonPolicy,
+}
+
+impl Default for SessionConfig {
+    fn default() -> Self {
+        Self {
+            idle_timeout: DEFAULT_IDLE_TIMEOUT,
+            abandon:      AbandonPolicy::default(),
+        }
+    }
+}
+
+/// A game between a human and MENACE.
+#[derive(Debug, Clone)]
+pub struct Session {
+    /// The authoritative state of the game.
+    pub game:        Game,
+    /// The symbol the human plays.
+    pub human:       Symbol,
+    /// The moves played, in order.
+    pub log:         Vec<MoveRecord>,
+    /// The beads MENACE has drawn in this game, held here while other games are played.
+    pub draws:       Vec<BeadDraw>,
+    /// How the game ended, once it has.
+    pub outcome:     Option<MatchOutcome>,
+    /// When the game started.
+    pub created:     Instant,
+    /// When the game was last played or viewed.
+    pub last_active: Instant,
+}
+
+impl Session {
+    /// Creates a session for a new game.
+    pub fn new(human: Symbol) -> Self {
+        let now = Instant::now();
+        Self {
+            game: Game::new(),
+            human,
+            log: Vec::new(),
+            draws: Vec::new(),
+            outcome: None,
+            created: now,
+            last_active: now,
+        }
+    }
+
+    /// Plays a move and records it in the log.
+    pub fn play(&mut self, coord: Coord) -> Result<(), tictacrustle::GameError> {
+        let player = self.game.status().to_move().unwrap_or(self.human);
+        self.game.play(coord)?;
+        self.log.push(MoveRecord {
+            player,
+            coord,
+            elapsed_ms: millis(self.created.elapsed()),
+        });
+        Ok(())
+    }
+
+    /// Marks the session as active now.
+    pub fn touch(&mut self) {
+        self.last_active = Instant::now();
+    }
+
+    /// Checks whether the session has gone without a request for at least `timeout` at `now`.
+    pub fn is_idle(&self, now: Instant, timeout: Duration) -> bool {
+        now.saturating_duration_since(self.last_active) >= timeout
+    }
+
+    /// Returns where the game stands.
+    pub fn state(&self) -> GameState {
+        match (self.outcome, self.game.status()) {
+            (Some(MatchOutcome::Resigned(_)), _) => GameState::Resigned,
+            (_, GameStatus::Won { .. }) => GameState::Won,
+            (_, GameStatus::Draw) => GameState::Draw,
+            (_, GameStatus::InProgress { .. }) => GameState::InProgress,
+        }
+    }
+
+    /// Describes the game for a client.
+    pub fn view(&self, id: &str) -> GameView {
+        let state = self.state();
+        GameView {
+            id: id.to_string(),
+            board: BoardCodec::new().encode(self.game.board()),
+            human: self.human,
+            menace: self.human.opponent(),
+            state,
+            to_move: if state.is_over() {
+                None
+            } else {
+                self.game.status().to_move()
+            },
+            winner: self.outcome.and_then(|outcome| outcome.winner()),
+            moves: self.log.iter().map(|record| record.coord).collect(),
+        }
+    }
+
+    /// Reports how the game ended, or `None` if it is still being played.
+    pub fn outcome_view(&self, id: &str) -> Option<OutcomeView> {
+        let outcome = self.outcome?;
+        Some(OutcomeView {
+            id:     id.to_string(),
+            state:  self.state(),
+            winner: outcome.winner(),
+            result: outcome.result_for(self.human),
+        })
+    }
+
+    /// Builds the events that tell a watching client where the game stands: the board, followed
+    /// by the player to move or the outcome.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The identifier of the game.
+    /// * `last_move` - The move that changed the board, if the events announce one.
+    pub fn events(&self, id: &str, last_move: Option<MoveRecord>) -> Vec<GameEvent> {
+        let board = GameEvent::Board {
+            game: self.view(id),
+            last_move,
+        };
+        let status = match (self.outcome_view(id), self.game.status().to_move()) {
+            (Some(outcome), _) => Some(GameEvent::Outcome { outcome }),
+            (None, Some(to_move)) => Some(GameEvent::Turn {
+                id: id.to_string(),
+                to_move,
+            }),
+            (None, None) => None,
+        };
+        std::iter::once(board).chain(status).collect()
+    }
+
+    /// Describes the session for an administrator.
+    pub fn summary(&self, id: &str, now: Instant, spectators: usize) -> SessionSummary {
+        SessionSummary {
+            id: id.to_string(),
+            human: self.human,
+            state: self.state(),
+            moves: self.log.len(),
+            age_secs: now.saturating_duration_since(self.created).as_secs(),
+            idle_secs: now.saturating_duration_since(self.last_active).as_secs(),
+            spectators,
+        }
+    }
+}
+
+/// Converts a duration to whole milliseconds, saturating if it does not fit.
+pub fn millis(duration: Duration) -> u64 {
+    u64::try_from(duration.as_millis()).unwrap_or(u64::MAX)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_abandon_policy_round_trip() {
+        for policy in [
+            AbandonPolicy::Ignore,
+            AbandonPolicy::Reinforce(GameResult::Win),
+            AbandonPolicy::Reinforce(GameResult::Draw),
+            AbandonPolicy::Reinforce(GameResult::Loss),
+        ] {
+            assert_eq!(policy.to_string().parse::<AbandonPolicy>().unwrap(), policy);
+        }
+        assert_eq!(
+            "LOSS".parse::<AbandonPolicy>().unwrap(),
+            AbandonPolicy::default()
+        );
+        assert!("forfeit".parse::<AbandonPolicy>().is_err());
+    }
+
+    #[test]
+    fn test_log_records_each_player() {
+        let mut session = Session::new(Symbol::O);
+        session.play(Coord::new(2, 2).unwrap()).unwrap();
+        session.play(Coord::new(1, 1).unwrap()).unwrap();
+        assert!(session.play(Coord::new(1, 1).unwrap()).is_err());
+
+        let players: Vec<Symbol> = session.log.iter().map(|record| record.player).collect();
+        assert_eq!(players, vec![Symbol::X, Symbol::O]);
+        assert_eq!(session.view("a").moves.len(), 2);
+    }
+
+    #[test]
+    fn test_idle() {
+        let session = Session::new(Symbol::X);
+        let timeout = Duration::from_secs(60);
+        assert!(!session.is_idle(Instant::now(), timeout));
+        assert!(session.is_idle(Instant::now() + timeout, timeout));
+        assert_eq!(
+            session.summary("a", Instant::now() + timeout, 0).idle_secs,
+            60
+        );
+    }
+
+    #[test]
+    fn test_events() {
+        let mut session = Session::new(Symbol::X);
+        let events = session.events("a", None);
+        assert_eq!(events.len(), 2);
+        assert_eq!(
+            events[1],
+            GameEvent::Turn {
+                id:      "a".to_string(),
+                to_move: Symbol::X,
+            }
+        );
+
+        session.outcome = Some(MatchOutcome::Resigned(Symbol::O));
+        let events = session.events("a", None);
+        assert!(matches!(
+            &events[1],
+            GameEvent::Outcome { outcome } if outcome.result == GameResult::Win
+        ));
+    }
+}
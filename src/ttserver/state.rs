@@ -0,0 +1,948 @@
+// SPDX-FileCopyrightText: 2022 - 2024 Ali Sajid Imami
+//
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use std::{
+    collections::HashMap,
+    fmt,
+    time::Instant,
+};
+
+use log::{
+    error,
+    info,
+};
+use rand::Rng;
+use tictacrustle::{
+    Coord,
+    GameError,
+    GameEvent,
+    GameResult,
+    GameView,
+    JoinRequest,
+    LobbyEntry,
+    MatchMove,
+    MatchOutcome,
+    MatchView,
+    MatchboxStore,
+    Menace,
+    MoveRecord,
+    MoveResponse,
+    NewGameRequest,
+    OutcomeView,
+    PlayerTicket,
+    SeededRng,
+    SessionSummary,
+    Symbol,
+};
+
+use crate::{
+    hub::{
+        Hub,
+        Subscription,
+    },
+    lobby::{
+        Match,
+        Seat,
+    },
+    session::{
+        AbandonPolicy,
+        Session,
+        SessionConfig,
+    },
+};
+
+/// An error answered to a client, along with its HTTP status.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ApiError {
+    /// The request is malformed. Answered with `400 Bad Request`.
+    BadRequest(String),
+    /// The request lacks the credentials an endpoint needs. Answered with `401 Unauthorized`.
+    Unauthorized(String),
+    /// No game or route matches the request. Answered with `404 Not Found`.
+    NotFound(String),
+    /// The request carries a token that does not belong to the game. Answered with
+    /// `403 Forbidden`.
+    Forbidden(String),
+    /// The request is not allowed in the current state of the game. Answered with
+    /// `409 Conflict`.
+    Conflict(String),
+    /// The request body is larger than the server accepts. Answered with
+    /// `413 Payload Too Large`.
+    PayloadTooLarge(String),
+    /// The server failed to answer a valid request. Answered with `500 Internal Server Error`.
+    Internal(String),
+    /// The server state cannot be used, because a thread panicked while holding it. Answered
+    /// with `503 Service Unavailable`.
+    Unavailable(String),
+}
+
+impl ApiError {
+    /// Returns the HTTP status code of the error.
+    pub const fn status(&self) -> u16 {
+        match self {
+            Self::BadRequest(_) => 400,
+            Self::Unauthorized(_) => 401,
+            Self::Forbidden(_) => 403,
+            Self::NotFound(_) => 404,
+            Self::Conflict(_) => 409,
+            Self::PayloadTooLarge(_) => 413,
+            Self::Internal(_) => 500,
+            Self::Unavailable(_) => 503,
+        }
+    }
+}
+
+impl fmt::Display for ApiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::BadRequest(message)
+            | Self::Unauthorized(message)
+            | Self::NotFound(message)
+            | Self::Forbidden(message)
+            | Self::Conflict(message)
+            | Self::PayloadTooLarge(message)
+            | Self::Internal(message)
+            | Self::Unavailable(message) => write!(f, "{message}"),
+        }
+    }
+}
+
+impl From<GameError> for ApiError {
+    fn from(error: GameError) -> Self {
+        match error {
+            GameError::InvalidSquare | GameError::InvalidEncoding | GameError::InvalidSymbol => {
+                Self::BadRequest(error.to_string())
+            }
+            _ => Self::Conflict(error.to_string()),
+        }
+    }
+}
+
+/// Everything the server knows: MENACE, where it is stored, and the games being played against
+/// it or between humans.
+pub struct ServerState {
+    /// The engine every game is played against.
+    menace:      Menace,
+    /// Where MENACE's matchboxes are committed after every game.
+    store:       Box<dyn MatchboxStore + Send>,
+    /// The random number generator used for bead draws.
+    rng:         SeededRng,
+    /// How games are expired.
+    config:      SessionConfig,
+    /// The bearer token that unlocks the admin endpoints, if they are enabled.
+    admin_token: Option<String>,
+    /// The games against MENACE, keyed by their identifier.
+    games:       HashMap<String, Session>,
+    /// The games between humans, keyed by their identifier.
+    matches:     HashMap<String, Match>,
+    /// The clients watching games.
+    hub:         Hub,
+}
+
+impl ServerState {
+    /// Creates the server state.
+    ///
+    /// # Arguments
+    ///
+    /// * `menace` - The engine every game is played against.
+    /// * `store` - Where MENACE's matchboxes are committed after every game.
+    /// * `rng` - The random number generator used for bead draws and game identifiers.
+    /// * `config` - How games are expired.
+    /// * `admin_token` - The bearer token that unlocks the admin endpoints, or `None` to disable
+    ///   them.
+    pub fn new(
+        menace: Menace,
+        store: Box<dyn MatchboxStore + Send>,
+        rng: SeededRng,
+        config: SessionConfig,
+        admin_token: Option<String>,
+    ) -> Self {
+        Self {
+            menace,
+            store,
+            rng,
+            config,
+            admin_token,
+            games: HashMap::new(),
+            matches: HashMap::new(),
+            hub: Hub::new(),
+        }
+    }
+
+    /// Starts a game, letting MENACE open if it plays first.
+    pub fn create_game(&mut self, request: NewGameRequest) -> GameView {
+        let id = self.new_id();
+
+        let human = if request.menace_first {
+            Symbol::O
+        } else {
+            Symbol::X
+        };
+        let mut session = Session::new(human);
+        if request.menace_first {
+            self.menace_move(&mut session);
+        }
+
+        let view = session.view(&id);
+        info!("Created game {id}; the human plays {human}");
+        self.games.insert(id, session);
+        view
+    }
+
+    /// Plays the human's move in a game, followed by MENACE's reply.
+    ///
+    /// # Errors
+    ///
+    /// - [`ApiError::NotFound`] if there is no such game.
+    /// - [`ApiError::Conflict`] if the game is over, it is not the human's turn, or the square is
+    ///   taken.
+    pub fn play(&mut self, id: &str, coord: Coord) -> Result<MoveResponse, ApiError> {
+        let mut session = self.games.remove(id).ok_or_else(|| not_found(id))?;
+        session.touch();
+        let result = self.play_session(&mut session, coord);
+        let view = session.view(id);
+        if result.is_ok() {
+            for event in session.events(id, session.log.last().copied()) {
+                self.hub.publish(id, &event);
+            }
+        }
+        self.games.insert(id.to_string(), session);
+
+        result.map(|menace_move| MoveResponse {
+            human_move: coord,
+            menace_move,
+            game: view,
+        })
+    }
+
+    /// Describes a game.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ApiError::NotFound`] if there is no such game.
+    pub fn view(&mut self, id: &str) -> Result<GameView, ApiError> {
+        let session = self.games.get_mut(id).ok_or_else(|| not_found(id))?;
+        session.touch();
+        Ok(session.view(id))
+    }
+
+    /// Subscribes to the events of a game against MENACE or between humans, without counting as
+    /// activity on it.
+    ///
+    /// The returned events describe where the game stands now, and should be sent before any
+    /// received on the channel.
+    ///
+    /// # Errors
+    ///
+    /// - [`ApiError::NotFound`] if there is no such game.
+    /// - [`ApiError::Unavailable`] if the game or the server already has as many spectators as the
+    ///   [`Hub`] allows.
+    pub fn subscribe(&mut self, id: &str) -> Result<(Vec<GameEvent>, Subscription), ApiError> {
+        let events = match (self.games.get(id), self.matches.get(id)) {
+            (Some(session), _) => session.events(id, None),
+            (None, Some(game)) => game.events(id, None),
+            (None, None) => return Err(not_found(id)),
+        };
+        let subscription = self.hub.subscribe(id).ok_or_else(|| {
+            ApiError::Unavailable(format!("Game {id} cannot take any more spectators"))
+        })?;
+        Ok((events, subscription))
+    }
+
+    /// Returns the log of the moves played in a game.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ApiError::NotFound`] if there is no such game.
+    pub fn log(&self, id: &str) -> Result<Vec<MoveRecord>, ApiError> {
+        self.games
+            .get(id)
+            .map(|session| session.log.clone())
+            .ok_or_else(|| not_found(id))
+    }
+
+    /// Lists the games being kept, oldest first.
+    pub fn sessions(&self) -> Vec<SessionSummary> {
+        let now = Instant::now();
+        let mut sessions: Vec<(&String, &Session)> = self.games.iter().collect();
+        sessions.sort_by_key(|(id, session)| (session.created, id.as_str()));
+        sessions
+            .into_iter()
+            .map(|(id, session)| session.summary(id, now, self.hub.subscribers(id)))
+            .collect()
+    }
+
+    /// Checks that a request may use the admin endpoints.
+    ///
+    /// # Arguments
+    ///
+    /// * `authorization` - The `Authorization` header of the request, if any.
+    ///
+    /// # Errors
+    ///
+    /// - [`ApiError::Forbidden`] if no admin token is configured.
+    /// - [`ApiError::Unauthorized`] if the header is not `Bearer` followed by the admin token.
+    pub fn authorize_admin(&self, authorization: Option<&str>) -> Result<(), ApiError> {
+        let Some(token) = &self.admin_token else {
+            return Err(ApiError::Forbidden(
+                "The admin endpoints are disabled; set TTSERVER_ADMIN_TOKEN to enable them".into(),
+            ));
+        };
+        let given = authorization.and_then(|header| header.strip_prefix("Bearer "));
+        if given.map(str::trim) == Some(token.as_str()) {
+            Ok(())
+        } else {
+            Err(ApiError::Unauthorized(
+                "The admin endpoints need the admin token".into(),
+            ))
+        }
+    }
+
+    /// Drops every game that has gone without a request for the idle timeout.
+    ///
+    /// Games against MENACE that are not over yet are abandoned, and MENACE is reinforced
+    /// according to the [`AbandonPolicy`], unless it had not drawn a bead yet. Games between
+    /// humans that are still being played are instead forfeited by the player to move, and both
+    /// players are told; they are dropped once they have gone another idle timeout without
+    /// changing. Other games between humans are simply dropped.
+    ///
+    /// # Arguments
+    ///
+    /// * `now` - The time to measure idleness against.
+    ///
+    /// # Returns
+    ///
+    /// The number of games dropped or forfeited.
+    pub fn expire_idle(&mut self, now: Instant) -> usize {
+        let timeout = self.config.idle_timeout;
+        let expired: Vec<String> = self
+            .games
+            .iter()
+            .filter(|(_, session)| session.is_idle(now, timeout))
+            .map(|(id, _)| id.clone())
+            .collect();
+
+        for id in &expired {
+            self.hub.close(id, &GameEvent::Expired { id: id.clone() });
+            let Some(mut session) = self.games.remove(id) else {
+                continue;
+            };
+            if session.outcome.is_some() {
+                continue;
+            }
+            if session.draws.is_empty() {
+                info!("Game {id} was abandoned before MENACE moved; MENACE is not reinforced");
+                continue;
+            }
+            match self.config.abandon {
+                AbandonPolicy::Ignore => {
+                    info!("Game {id} was abandoned; MENACE is not reinforced");
+                }
+                AbandonPolicy::Reinforce(result) => {
+                    info!("Game {id} was abandoned; it counts as a {result} for the human");
+                    self.reinforce(&mut session, result.opposite());
+                }
+            }
+        }
+
+        let idle_matches: Vec<String> = self
+            .matches
+            .iter()
+            .filter(|(_, game)| game.is_idle(now, timeout))
+            .map(|(id, _)| id.clone())
+            .collect();
+        for id in &idle_matches {
+            let Some(game) = self.matches.get_mut(id) else {
+                continue;
+            };
+            if let Some(player) = game.forfeit(now) {
+                info!("{player} forfeited lobby game {id} by going idle on their turn");
+                for event in game.events(id, None) {
+                    self.hub.publish(id, &event);
+                }
+            } else {
+                self.hub.close(id, &GameEvent::Expired { id: id.clone() });
+                self.matches.remove(id);
+                info!("Dropped the idle lobby game {id}");
+            }
+        }
+        expired.len() + idle_matches.len()
+    }
+
+    /// Opens a game in the lobby. The player who opens it plays `X`.
+    pub fn open_match(&mut self, request: JoinRequest) -> PlayerTicket {
+        let id = self.new_id();
+        let token = self.new_token();
+        let game = Match::new(Seat::new(request.name, Symbol::X, token.clone()));
+
+        let ticket = PlayerTicket {
+            id: id.clone(),
+            token,
+            symbol: Symbol::X,
+            game: game.view(&id),
+        };
+        info!("{} opened lobby game {id}", game.x.name);
+        self.matches.insert(id, game);
+        ticket
+    }
+
+    /// Lists the games in the lobby waiting for a second player, oldest first.
+    pub fn lobby(&self) -> Vec<LobbyEntry> {
+        let now = Instant::now();
+        let mut waiting: Vec<(&String, &Match)> = self
+            .matches
+            .iter()
+            .filter(|(_, game)| game.o.is_none())
+            .collect();
+        waiting.sort_by_key(|(id, game)| (game.created, id.as_str()));
+        waiting
+            .into_iter()
+            .map(|(id, game)| game.entry(id, now))
+            .collect()
+    }
+
+    /// Joins a game in the lobby as `O`, and tells the players it has started.
+    ///
+    /// # Errors
+    ///
+    /// - [`ApiError::NotFound`] if there is no such game.
+    /// - [`ApiError::Conflict`] if the game already has two players.
+    pub fn join_match(&mut self, id: &str, request: JoinRequest) -> Result<PlayerTicket, ApiError> {
+        let token = self.new_token();
+        let game = self.matches.get_mut(id).ok_or_else(|| not_found(id))?;
+        let guest = Seat::new(request.name, Symbol::O, token.clone());
+        info!("{} joined lobby game {id}", guest.name);
+        game.join(guest)?;
+
+        let events = game.events(id, None);
+        let view = game.view(id);
+        for event in &events {
+            self.hub.publish(id, event);
+        }
+        Ok(PlayerTicket {
+            id: id.to_string(),
+            token,
+            symbol: Symbol::O,
+            game: view,
+        })
+    }
+
+    /// Describes a game between humans, without counting as activity on it, so that a player
+    /// waiting for their opponent cannot keep the game alive by polling.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ApiError::NotFound`] if there is no such game.
+    pub fn match_view(&self, id: &str) -> Result<MatchView, ApiError> {
+        self.matches
+            .get(id)
+            .map(|game| game.view(id))
+            .ok_or_else(|| not_found(id))
+    }
+
+    /// Plays a move in a game between humans, and tells both players about it.
+    ///
+    /// # Errors
+    ///
+    /// - [`ApiError::NotFound`] if there is no such game.
+    /// - [`ApiError::Forbidden`] if the token belongs to neither player.
+    /// - [`ApiError::Conflict`] if the game is not being played, it is not the player's turn, or
+    ///   the square is taken.
+    pub fn play_match(&mut self, id: &str, request: &MatchMove) -> Result<MatchView, ApiError> {
+        let game = self.matches.get_mut(id).ok_or_else(|| not_found(id))?;
+        let record = game.play(&request.token, request.coord)?;
+
+        let events = game.events(id, Some(record));
+        let view = game.view(id);
+        if view.state.is_over() {
+            info!("Lobby game {id} ended: {:?}", view.state);
+        }
+        for event in &events {
+            self.hub.publish(id, event);
+        }
+        Ok(view)
+    }
+
+    /// Reports how a finished game ended.
+    ///
+    /// # Errors
+    ///
+    /// - [`ApiError::NotFound`] if there is no such game.
+    /// - [`ApiError::Conflict`] if the game is still being played.
+    pub fn outcome(&self, id: &str) -> Result<OutcomeView, ApiError> {
+        let session = self.games.get(id).ok_or_else(|| not_found(id))?;
+        session
+            .outcome_view(id)
+            .ok_or_else(|| ApiError::Conflict(format!("Game {id} is still in progress")))
+    }
+
+    /// Plays the human's move and MENACE's reply in a session taken out of the map.
+    fn play_session(
+        &mut self,
+        session: &mut Session,
+        coord: Coord,
+    ) -> Result<Option<Coord>, ApiError> {
+        if session.outcome.is_some() {
+            return Err(ApiError::Conflict("The game is over".to_string()));
+        }
+        if session.game.status().to_move() != Some(session.human) {
+            return Err(ApiError::Conflict("It is not your turn".to_string()));
+        }
+
+        session.play(coord)?;
+        if self.check_finished(session) {
+            return Ok(None);
+        }
+        Ok(self.menace_move(session))
+    }
+
+    /// Lets MENACE move, or resign, and finishes the game if it is over.
+    fn menace_move(&mut self, session: &mut Session) -> Option<Coord> {
+        self.menace
+            .restore_history(std::mem::take(&mut session.draws));
+        let choice = self.menace.choose_move(session.game.board(), &mut self.rng);
+        session.draws = self.menace.take_history();
+
+        let played = choice.filter(|&coord| session.play(coord).is_ok());
+        if played.is_some() {
+            self.check_finished(session);
+        } else {
+            self.finish(session, MatchOutcome::Resigned(session.human.opponent()));
+        }
+        played
+    }
+
+    /// Finishes the game if the board shows it is over, and reports whether it did.
+    fn check_finished(&mut self, session: &mut Session) -> bool {
+        let status = session.game.status();
+        if status.is_over() {
+            self.finish(session, MatchOutcome::Finished(status));
+        }
+        status.is_over()
+    }
+
+    /// Records the outcome of a game and reinforces MENACE.
+    fn finish(&mut self, session: &mut Session, outcome: MatchOutcome) {
+        session.outcome = Some(outcome);
+        self.reinforce(session, outcome.result_for(session.human.opponent()));
+    }
+
+    /// Reinforces MENACE for the beads drawn in a session and commits the matchboxes.
+    fn reinforce(&mut self, session: &mut Session, result: GameResult) {
+        self.menace
+            .restore_history(std::mem::take(&mut session.draws));
+        if let Err(err) = self.menace.reinforce_into(result, self.store.as_mut()) {
+            error!("Could not store the reinforced matchboxes: {err}");
+        }
+        info!(
+            "MENACE finished a game with a {result}; {} games played",
+            self.menace.games_played()
+        );
+    }
+
+    /// Picks a random identifier no current game uses.
+    fn new_id(&mut self) -> String {
+        loop {
+            let id = format!("{:016x}", self.rng.random::<u64>());
+            if !self.games.contains_key(&id) && !self.matches.contains_key(&id) {
+                return id;
+            }
+        }
+    }
+
+    /// Picks a random secret for a player of a game between humans.
+    fn new_token(&mut self) -> String {
+        format!("{:032x}", self.rng.random::<u128>())
+    }
+}
+
+/// Builds the error for a game that does not exist.
+fn not_found(id: &str) -> ApiError {
+    ApiError::NotFound(format!("No game with id {id}"))
+}
+
+#[cfg(test)]
+pub mod tests {
+    use rand::SeedableRng;
+    use tictacrustle::{
+        GameResult,
+        GameState,
+        MemoryStore,
+    };
+
+    use super::*;
+    use crate::hub::MAX_SUBSCRIBERS_PER_GAME;
+
+    /// Creates a server with a new MENACE kept in memory and a seeded random number generator,
+    /// for the tests of every module.
+    pub fn state() -> ServerState {
+        with_policy(AbandonPolicy::default())
+    }
+
+    fn with_policy(abandon: AbandonPolicy) -> ServerState {
+        ServerState::new(
+            Menace::new(),
+            Box::new(MemoryStore::new()),
+            SeededRng::seed_from_u64(1),
+            SessionConfig {
+                abandon,
+                ..SessionConfig::default()
+            },
+            Some("secret".into()),
+        )
+    }
+
+    fn expired(state: &ServerState) -> Instant {
+        Instant::now() + state.config.idle_timeout
+    }
+
+    fn coord(row: usize, col: usize) -> Coord {
+        Coord::new(row, col).unwrap()
+    }
+
+    #[test]
+    fn test_menace_replies_to_each_move() {
+        let mut state = state();
+        let game = state.create_game(NewGameRequest::default());
+        assert_eq!(game.human, Symbol::X);
+        assert_eq!(game.to_move, Some(Symbol::X));
+
+        let response = state.play(&game.id, coord(2, 2)).unwrap();
+        let reply = response.menace_move.unwrap();
+        assert_eq!(response.game.moves, vec![coord(2, 2), reply]);
+        assert_eq!(response.game.to_move, Some(Symbol::X));
+        assert_eq!(state.view(&game.id).unwrap(), response.game);
+    }
+
+    #[test]
+    fn test_menace_can_move_first() {
+        let mut state = state();
+        let game = state.create_game(NewGameRequest { menace_first: true });
+        assert_eq!(game.human, Symbol::O);
+        assert_eq!(game.moves.len(), 1);
+        assert_eq!(game.to_move, Some(Symbol::O));
+    }
+
+    #[test]
+    fn test_illegal_moves() {
+        let mut state = state();
+        let game = state.create_game(NewGameRequest::default());
+        let response = state.play(&game.id, coord(1, 1)).unwrap();
+        let taken = response.menace_move.unwrap();
+
+        assert!(matches!(
+            state.play(&game.id, taken),
+            Err(ApiError::Conflict(_))
+        ));
+        assert!(matches!(
+            state.play("missing", coord(1, 1)),
+            Err(ApiError::NotFound(_))
+        ));
+        assert!(matches!(
+            state.outcome(&game.id),
+            Err(ApiError::Conflict(_))
+        ));
+    }
+
+    #[test]
+    fn test_finished_game_reinforces_menace() {
+        let mut state = state();
+        let game = state.create_game(NewGameRequest::default());
+
+        let mut view = game;
+        while !view.state.is_over() {
+            let board = view.decode_board().unwrap();
+            let next = board.legal_moves()[0];
+            view = state.play(&view.id, next).unwrap().game;
+        }
+
+        let outcome = state.outcome(&view.id).unwrap();
+        assert_eq!(outcome.state, view.state);
+        assert_eq!(state.menace.games_played(), 1);
+        assert!(state.menace.history().is_empty());
+        assert!(matches!(
+            state.play(&view.id, coord(1, 1)),
+            Err(ApiError::Conflict(_))
+        ));
+        if outcome.result == GameResult::Win {
+            assert_eq!(outcome.winner, Some(Symbol::X));
+        }
+    }
+
+    #[test]
+    fn test_interleaved_games_keep_their_own_draws() {
+        let mut state = state();
+        let first = state.create_game(NewGameRequest::default());
+        let second = state.create_game(NewGameRequest { menace_first: true });
+        state.play(&first.id, coord(2, 2)).unwrap();
+
+        assert!(state.menace.history().is_empty());
+        assert_eq!(state.games[&first.id].draws.len(), 1);
+        assert_eq!(state.games[&second.id].draws.len(), 1);
+    }
+
+    #[test]
+    fn test_ids_are_unique_and_opaque() {
+        let mut state = state();
+        let first = state.create_game(NewGameRequest::default());
+        let second = state.create_game(NewGameRequest::default());
+        assert_ne!(first.id, second.id);
+        assert_eq!(first.id.len(), 16);
+        assert!(first.id.chars().all(|c| c.is_ascii_hexdigit()));
+    }
+
+    #[test]
+    fn test_log_and_sessions() {
+        let mut state = state();
+        let game = state.create_game(NewGameRequest { menace_first: true });
+        let board = game.decode_board().unwrap();
+        state.play(&game.id, board.legal_moves()[0]).unwrap();
+
+        let log = state.log(&game.id).unwrap();
+        let players: Vec<Symbol> = log.iter().map(|record| record.player).collect();
+        assert_eq!(players, vec![Symbol::X, Symbol::O, Symbol::X]);
+        assert!(matches!(state.log("missing"), Err(ApiError::NotFound(_))));
+
+        let sessions = state.sessions();
+        assert_eq!(sessions.len(), 1);
+        assert_eq!(sessions[0].id, game.id);
+        assert_eq!(sessions[0].moves, 3);
+    }
+
+    #[test]
+    fn test_admin_token() {
+        let state = state();
+        assert_eq!(state.authorize_admin(Some("Bearer secret")), Ok(()));
+        assert!(matches!(
+            state.authorize_admin(Some("Bearer guess")),
+            Err(ApiError::Unauthorized(_))
+        ));
+        assert!(matches!(
+            state.authorize_admin(None),
+            Err(ApiError::Unauthorized(_))
+        ));
+
+        let mut state = state;
+        state.admin_token = None;
+        assert!(matches!(
+            state.authorize_admin(Some("Bearer secret")),
+            Err(ApiError::Forbidden(_))
+        ));
+    }
+
+    #[test]
+    fn test_abandoned_game_is_reinforced() {
+        let mut state = state();
+        let game = state.create_game(NewGameRequest { menace_first: true });
+        let before = state.menace.total_beads();
+
+        assert_eq!(state.expire_idle(Instant::now()), 0);
+        assert_eq!(state.expire_idle(expired(&state)), 1);
+        assert!(matches!(state.view(&game.id), Err(ApiError::NotFound(_))));
+        assert_eq!(state.menace.games_played(), 1);
+        assert!(state.menace.total_beads() > before);
+        assert_eq!(state.store.games_played().unwrap(), 1);
+    }
+
+    #[test]
+    fn test_game_abandoned_before_menace_moved_is_not_reinforced() {
+        let mut state = state();
+        state.create_game(NewGameRequest::default());
+        let before = state.menace.total_beads();
+
+        assert_eq!(state.expire_idle(expired(&state)), 1);
+        assert_eq!(state.menace.games_played(), 0);
+        assert_eq!(state.menace.total_beads(), before);
+        assert_eq!(state.store.games_played().unwrap(), 0);
+    }
+
+    #[test]
+    fn test_ignored_abandonment() {
+        let mut state = with_policy(AbandonPolicy::Ignore);
+        let game = state.create_game(NewGameRequest { menace_first: true });
+        assert_eq!(state.expire_idle(expired(&state)), 1);
+        assert!(state.sessions().is_empty());
+        assert_eq!(state.menace.games_played(), 0);
+        assert!(state.menace.history().is_empty());
+        assert!(matches!(
+            state.play(&game.id, coord(1, 1)),
+            Err(ApiError::NotFound(_))
+        ));
+    }
+
+    #[test]
+    fn test_spectators_follow_the_game() {
+        let mut state = state();
+        let game = state.create_game(NewGameRequest::default());
+        let (snapshot, events) = state.subscribe(&game.id).unwrap();
+        assert_eq!(
+            snapshot[0],
+            GameEvent::Board {
+                game:      game.clone(),
+                last_move: None,
+            }
+        );
+        assert!(matches!(
+            state.subscribe("missing"),
+            Err(ApiError::NotFound(_))
+        ));
+        assert_eq!(state.sessions()[0].spectators, 1);
+
+        let crowd: Vec<_> = (1..MAX_SUBSCRIBERS_PER_GAME)
+            .map(|_| state.subscribe(&game.id).unwrap())
+            .collect();
+        assert!(matches!(
+            state.subscribe(&game.id),
+            Err(ApiError::Unavailable(_))
+        ));
+        drop(crowd);
+
+        let response = state.play(&game.id, coord(2, 2)).unwrap();
+        let GameEvent::Board {
+            game: pushed,
+            last_move,
+        } = events.try_recv().unwrap()
+        else {
+            panic!("expected a board update");
+        };
+        assert_eq!(pushed, response.game);
+        assert_eq!(last_move.map(|record| record.coord), response.menace_move);
+        assert!(matches!(
+            events.try_recv().unwrap(),
+            GameEvent::Turn {
+                to_move: Symbol::X,
+                ..
+            }
+        ));
+
+        state.expire_idle(expired(&state));
+        assert_eq!(
+            events.try_recv().unwrap(),
+            GameEvent::Expired { id: game.id }
+        );
+        assert!(events.recv().is_err());
+    }
+
+    #[test]
+    fn test_lobby_pairs_two_players() {
+        let mut state = state();
+        let host = state.open_match(JoinRequest {
+            name: Some("Ann".to_string()),
+        });
+        assert_eq!(host.symbol, Symbol::X);
+        assert_eq!(host.game.state, GameState::Waiting);
+        assert_eq!(state.lobby().len(), 1);
+        assert_eq!(state.lobby()[0].host, "Ann");
+        let (_, events) = state.subscribe(&host.id).unwrap();
+
+        let guest = state.join_match(&host.id, JoinRequest::default()).unwrap();
+        assert_eq!(guest.symbol, Symbol::O);
+        assert_ne!(guest.token, host.token);
+        assert_eq!(guest.game.to_move, Some(Symbol::X));
+        assert!(state.lobby().is_empty());
+        assert!(matches!(
+            state.join_match(&host.id, JoinRequest::default()),
+            Err(ApiError::Conflict(_))
+        ));
+        assert!(matches!(
+            events.try_recv().unwrap(),
+            GameEvent::Match { .. }
+        ));
+        assert!(matches!(
+            events.try_recv().unwrap(),
+            GameEvent::Turn {
+                to_move: Symbol::X,
+                ..
+            }
+        ));
+
+        let play = |token: &str, row, col| MatchMove {
+            token: token.to_string(),
+            coord: coord(row, col),
+        };
+        assert!(matches!(
+            state.play_match(&host.id, &play(&guest.token, 1, 1)),
+            Err(ApiError::Conflict(_))
+        ));
+        assert!(matches!(
+            state.play_match(&host.id, &play("stranger", 1, 1)),
+            Err(ApiError::Forbidden(_))
+        ));
+        let view = state
+            .play_match(&host.id, &play(&host.token, 1, 1))
+            .unwrap();
+        assert_eq!(view.to_move, Some(Symbol::O));
+        assert_eq!(state.match_view(&host.id).unwrap(), view);
+        assert!(matches!(
+            events.try_recv().unwrap(),
+            GameEvent::Match {
+                last_move: Some(_),
+                ..
+            }
+        ));
+        assert!(matches!(
+            events.try_recv().unwrap(),
+            GameEvent::Turn {
+                to_move: Symbol::O,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn test_idle_player_forfeits() {
+        let mut state = state();
+        let host = state.open_match(JoinRequest::default());
+        let guest = state.join_match(&host.id, JoinRequest::default()).unwrap();
+        state
+            .play_match(
+                &host.id,
+                &MatchMove {
+                    token: host.token,
+                    coord: coord(1, 1),
+                },
+            )
+            .unwrap();
+        let (_, events) = state.subscribe(&host.id).unwrap();
+
+        // Polling does not keep the game alive while `O` is away.
+        state.match_view(&host.id).unwrap();
+        let now = expired(&state);
+        assert_eq!(state.expire_idle(now), 1);
+        let view = state.match_view(&guest.id).unwrap();
+        assert_eq!(view.state, GameState::Resigned);
+        assert_eq!(view.winner, Some(Symbol::X));
+        assert!(matches!(
+            events.try_recv().unwrap(),
+            GameEvent::Match { .. }
+        ));
+        assert_eq!(
+            events.try_recv().unwrap(),
+            GameEvent::Finished {
+                id:     host.id.clone(),
+                state:  GameState::Resigned,
+                winner: Some(Symbol::X),
+            }
+        );
+
+        assert_eq!(state.expire_idle(now + state.config.idle_timeout), 1);
+        assert_eq!(
+            events.try_recv().unwrap(),
+            GameEvent::Expired { id: host.id }
+        );
+    }
+
+    #[test]
+    fn test_idle_lobby_games_are_dropped() {
+        let mut state = state();
+        let host = state.open_match(JoinRequest::default());
+        assert_eq!(state.expire_idle(expired(&state)), 1);
+        assert!(matches!(
+            state.match_view(&host.id),
+            Err(ApiError::NotFound(_))
+        ));
+        assert_eq!(state.menace.games_played(), 0);
+    }
+}
@@ -0,0 +1,347 @@
+// SPDX-FileCopyrightText: 2022 - 2024 Ali Sajid Imami
+//
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Pushes the events of a game to clients over a WebSocket at `/games/{id}/events` for games
+//! against MENACE, or `/lobby/{id}/events` for games between humans.
+//!
+//! The sockets are served on a port of their own rather than through `tiny_http`, whose upgraded
+//! connections cannot be read without blocking. The server writes [`GameEvent`] messages as JSON
+//! text frames, and reads the socket only to answer pings and the client's Close frame. Moves are
+//! still played over the HTTP API.
+//!
+//! Every socket is served by its own thread, so the [`Hub`](crate::hub::Hub) limits how many can
+//! be open for one game and in total. Upgrades past those limits are answered with
+//! `503 Service Unavailable`.
+
+use std::{
+    io::ErrorKind,
+    net::{
+        TcpListener,
+        TcpStream,
+    },
+    sync::{
+        mpsc::{
+            Receiver,
+            TryRecvError,
+        },
+        Mutex,
+    },
+    thread,
+    time::{
+        Duration,
+        Instant,
+    },
+};
+
+use log::{
+    debug,
+    info,
+    warn,
+};
+use tictacrustle::GameEvent;
+use tungstenite::{
+    handshake::server::{
+        ErrorResponse,
+        Request,
+        Response,
+    },
+    http,
+    Message,
+    WebSocket,
+};
+
+use crate::{
+    api,
+    hub::Subscription,
+    state::{
+        ApiError,
+        ServerState,
+    },
+};
+
+/// How long a socket may go without a message before it is pinged, so dead clients are noticed.
+const KEEPALIVE: Duration = Duration::from_secs(30);
+
+/// How long the socket is read before checking for new events, which bounds how late an event is
+/// sent.
+const POLL: Duration = Duration::from_millis(100);
+
+/// How long a client may take to send its handshake before the connection is dropped.
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Accepts WebSocket connections until the listener fails, and streams the events of each game
+/// on a new thread.
+///
+/// Handshakes are read on the calling thread, each for at most [`HANDSHAKE_TIMEOUT`].
+///
+/// # Arguments
+///
+/// * `state` - The state the games are looked up in.
+/// * `listener` - The listener the clients connect to.
+pub fn listen(state: &Mutex<ServerState>, listener: &TcpListener) {
+    for stream in listener.incoming() {
+        match stream {
+            Ok(stream) => serve(state, stream),
+            Err(err) => warn!("Could not accept a WebSocket connection: {err}"),
+        }
+    }
+}
+
+/// Opens a WebSocket on a connection, and streams the events of its game on a new thread.
+///
+/// Requests for another path, for a game that does not exist, or for a game that already has as
+/// many spectators as allowed are answered with an error instead.
+pub fn serve(state: &Mutex<ServerState>, stream: TcpStream) {
+    if let Err(err) = stream.set_read_timeout(Some(HANDSHAKE_TIMEOUT)) {
+        warn!("Could not time out the WebSocket handshake: {err}");
+        return;
+    }
+
+    let mut opened = None;
+    // The error response type is fixed by `tungstenite`.
+    #[allow(clippy::result_large_err)]
+    let callback = |request: &Request, response: Response| {
+        let path = request.uri().path().to_string();
+        match open(state, &path) {
+            Ok((id, snapshot, events)) => {
+                info!("GET {path} -> 101");
+                opened = Some((id, snapshot, events));
+                Ok(response)
+            }
+            Err(error) => {
+                info!("GET {path} -> {}", error.status());
+                Err(error_response(&error))
+            }
+        }
+    };
+    let socket = match tungstenite::accept_hdr(stream, callback) {
+        Ok(socket) => socket,
+        Err(err) => {
+            debug!("Could not open a WebSocket: {err}");
+            return;
+        }
+    };
+    let Some((id, snapshot, events)) = opened else {
+        return;
+    };
+    if let Err(err) = socket.get_ref().set_read_timeout(Some(POLL)) {
+        warn!("Could not poll the event stream of game {id}: {err}");
+        return;
+    }
+
+    thread::spawn(move || match stream_events(socket, snapshot, &events) {
+        Ok(()) => debug!("Closed the event stream of game {id}"),
+        Err(err) => debug!("The event stream of game {id} ended: {err}"),
+    });
+}
+
+/// Checks the path of a WebSocket request and subscribes to its game.
+///
+/// Returns the identifier of the game, the events describing the game now, and the subscription
+/// to later events.
+fn open(
+    state: &Mutex<ServerState>,
+    path: &str,
+) -> Result<(String, Vec<GameEvent>, Subscription), ApiError> {
+    let segments: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+    let (["games", id, "events"] | ["lobby", id, "events"]) = segments.as_slice() else {
+        return Err(ApiError::NotFound(format!("No WebSocket at {path}")));
+    };
+
+    let mut state = state
+        .lock()
+        .map_err(|_| ApiError::Unavailable("The server state is unavailable".into()))?;
+    let (snapshot, events) = state.subscribe(id)?;
+    Ok((id.to_string(), snapshot, events))
+}
+
+/// Builds the HTTP response that turns down a WebSocket request.
+fn error_response(error: &ApiError) -> ErrorResponse {
+    let mut response = http::Response::new(Some(api::Response::error(error).body));
+    *response.status_mut() =
+        http::StatusCode::from_u16(error.status()).unwrap_or(http::StatusCode::BAD_REQUEST);
+    response.headers_mut().insert(
+        http::header::CONTENT_TYPE,
+        http::HeaderValue::from_static("application/json"),
+    );
+    response
+}
+
+/// Writes the snapshot of a game, then every event received, until the game is dropped or the
+/// client closes the socket or goes away.
+fn stream_events(
+    mut socket: WebSocket<TcpStream>,
+    snapshot: Vec<GameEvent>,
+    events: &Receiver<GameEvent>,
+) -> anyhow::Result<()> {
+    for event in &snapshot {
+        socket.send(message(event)?)?;
+    }
+    let mut last_sent = Instant::now();
+    loop {
+        match events.try_recv() {
+            Ok(event) => {
+                socket.send(message(&event)?)?;
+                last_sent = Instant::now();
+                continue;
+            }
+            Err(TryRecvError::Empty) => {}
+            Err(TryRecvError::Disconnected) => break,
+        }
+        if last_sent.elapsed() >= KEEPALIVE {
+            socket.send(Message::Ping(Vec::new().into()))?;
+            last_sent = Instant::now();
+        }
+
+        match socket.read() {
+            Ok(Message::Close(_)) => {
+                // Reading the Close frame queued the reply; flushing sends it.
+                return match socket.flush() {
+                    Ok(()) | Err(tungstenite::Error::ConnectionClosed) => Ok(()),
+                    Err(err) => Err(err.into()),
+                };
+            }
+            Ok(_) => {}
+            Err(tungstenite::Error::Io(err))
+                if matches!(err.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) => {}
+            Err(tungstenite::Error::ConnectionClosed) => return Ok(()),
+            Err(err) => return Err(err.into()),
+        }
+    }
+    socket.close(None)?;
+    socket.flush()?;
+    Ok(())
+}
+
+/// Encodes an event as a JSON text frame.
+fn message(event: &GameEvent) -> serde_json::Result<Message> {
+    serde_json::to_string(event).map(Message::text)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        net::SocketAddr,
+        sync::Arc,
+        thread::JoinHandle,
+    };
+
+    use tictacrustle::{
+        Coord,
+        GameView,
+        NewGameRequest,
+        Symbol,
+    };
+    use tungstenite::stream::MaybeTlsStream;
+
+    use super::*;
+    use crate::state::tests::state;
+
+    /// Serves the given number of WebSocket connections on a new listener.
+    fn listener(
+        state: &Arc<Mutex<ServerState>>,
+        connections: usize,
+    ) -> (SocketAddr, JoinHandle<()>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        let serving = Arc::clone(state);
+        let handle = thread::spawn(move || {
+            for stream in listener.incoming().take(connections) {
+                serve(&serving, stream.unwrap());
+            }
+        });
+        (address, handle)
+    }
+
+    fn receive(socket: &mut WebSocket<MaybeTlsStream<TcpStream>>) -> GameEvent {
+        loop {
+            if let Message::Text(text) = socket.read().unwrap() {
+                return serde_json::from_str(&text).unwrap();
+            }
+        }
+    }
+
+    #[test]
+    fn test_spectator_receives_updates() {
+        let state = Arc::new(Mutex::new(state()));
+        let game: GameView = state.lock().unwrap().create_game(NewGameRequest::default());
+
+        let (address, handle) = listener(&state, 2);
+
+        let missing = tungstenite::connect(format!("ws://{address}/games/missing/events"));
+        assert!(matches!(
+            missing,
+            Err(tungstenite::Error::Http(response)) if response.status() == 404
+        ));
+        let (mut socket, _) =
+            tungstenite::connect(format!("ws://{address}/games/{}/events", game.id)).unwrap();
+        handle.join().unwrap();
+
+        assert!(matches!(
+            receive(&mut socket),
+            GameEvent::Board {
+                last_move: None,
+                ..
+            }
+        ));
+        assert!(matches!(
+            receive(&mut socket),
+            GameEvent::Turn {
+                to_move: Symbol::X,
+                ..
+            }
+        ));
+
+        let response = state
+            .lock()
+            .unwrap()
+            .play(&game.id, Coord::new(2, 2).unwrap())
+            .unwrap();
+        assert_eq!(
+            receive(&mut socket),
+            GameEvent::Board {
+                game:      response.game.clone(),
+                last_move: state.lock().unwrap().log(&game.id).unwrap().last().copied(),
+            }
+        );
+        assert!(matches!(receive(&mut socket), GameEvent::Turn { .. }));
+    }
+
+    #[test]
+    fn test_client_close_is_answered() {
+        let state = Arc::new(Mutex::new(state()));
+        let game: GameView = state.lock().unwrap().create_game(NewGameRequest::default());
+        let (address, handle) = listener(&state, 1);
+
+        let (mut socket, _) =
+            tungstenite::connect(format!("ws://{address}/games/{}/events", game.id)).unwrap();
+        handle.join().unwrap();
+        if let MaybeTlsStream::Plain(stream) = socket.get_ref() {
+            stream
+                .set_read_timeout(Some(Duration::from_secs(5)))
+                .unwrap();
+        }
+        receive(&mut socket);
+        receive(&mut socket);
+
+        socket.close(None).unwrap();
+        let reply = loop {
+            match socket.read() {
+                Ok(_) => {}
+                Err(err) => break err,
+            }
+        };
+        assert!(matches!(reply, tungstenite::Error::ConnectionClosed));
+
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while state.lock().unwrap().sessions()[0].spectators > 0 {
+            assert!(
+                Instant::now() < deadline,
+                "the server kept the subscription"
+            );
+            thread::sleep(POLL);
+        }
+    }
+}